@@ -0,0 +1,253 @@
+use std::
+{
+    cmp::Ordering,
+    fmt,
+    ops
+};
+
+// Each limb holds 9 decimal digits, so printing doesn't need any division
+const LIMB_BASE: u64 = 1_000_000_000;
+
+// Arbitrary precision unsigned integer, just what the simulations need
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct BigUint
+{
+    // Least significant limb first, no trailing zero limbs (zero is empty)
+    limbs: Vec<u32>
+}
+
+impl BigUint
+{
+    pub fn zero() -> Self { Self::default() }
+
+    pub fn is_zero(&self) -> bool { self.limbs.is_empty() }
+
+    pub fn to_u128(&self) -> Option<u128>
+    {
+        let mut result: u128 = 0;
+        for &limb in self.limbs.iter().rev()
+        {
+            result = result.checked_mul(LIMB_BASE as u128)?
+                           .checked_add(limb as u128)?;
+        }
+        return Some(result);
+    }
+
+    pub fn rem_u32(&self, divisor: u32) -> u32
+    {
+        assert!(divisor > 0);
+
+        let mut remainder: u64 = 0;
+        for &limb in self.limbs.iter().rev()
+        {
+            remainder = (remainder * LIMB_BASE + limb as u64) % divisor as u64;
+        }
+        return remainder as u32;
+    }
+
+    pub fn get_num_digits(&self) -> usize
+    {
+        return match self.limbs.last()
+        {
+            None       => 1,
+            Some(last) => (self.limbs.len() - 1) * 9 + last.to_string().len()
+        };
+    }
+
+    fn trim(&mut self)
+    {
+        while self.limbs.last() == Some(&0)
+        {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u128> for BigUint
+{
+    fn from(mut value: u128) -> Self
+    {
+        let mut limbs = Vec::new();
+        while value > 0
+        {
+            limbs.push((value % LIMB_BASE as u128) as u32);
+            value /= LIMB_BASE as u128;
+        }
+        return Self{ limbs };
+    }
+}
+
+impl ops::AddAssign<&BigUint> for BigUint
+{
+    fn add_assign(&mut self, rhs: &BigUint)
+    {
+        if self.limbs.len() < rhs.limbs.len()
+        {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for i in 0..self.limbs.len()
+        {
+            let sum = self.limbs[i] as u64 + *rhs.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            self.limbs[i] = (sum % LIMB_BASE) as u32;
+            carry         = sum / LIMB_BASE;
+
+            if carry == 0 && i >= rhs.limbs.len() { break; }
+        }
+        if carry > 0 { self.limbs.push(carry as u32); }
+    }
+}
+
+impl ops::Add<&BigUint> for &BigUint
+{
+    type Output = BigUint;
+    fn add(self, rhs: &BigUint) -> BigUint
+    {
+        let mut result = self.clone();
+        result += rhs;
+        return result;
+    }
+}
+
+impl ops::Mul<&BigUint> for &BigUint
+{
+    type Output = BigUint;
+
+    // Schoolbook, one output column at a time so carries are only
+    // propagated once per column
+    fn mul(self, rhs: &BigUint) -> BigUint
+    {
+        if self.is_zero() || rhs.is_zero() { return BigUint::zero(); }
+
+        let a = &self.limbs;
+        let b = &rhs.limbs;
+
+        let mut limbs = Vec::with_capacity(a.len() + b.len());
+        let mut carry: u128 = 0;
+        for k in 0..a.len() + b.len() - 1
+        {
+            let first = k.saturating_sub(b.len() - 1);
+            let last  = k.min(a.len() - 1);
+
+            let mut column = carry;
+            for i in first..=last
+            {
+                column += a[i] as u128 * b[k - i] as u128;
+            }
+            limbs.push((column % LIMB_BASE as u128) as u32);
+            carry = column / LIMB_BASE as u128;
+        }
+        while carry > 0
+        {
+            limbs.push((carry % LIMB_BASE as u128) as u32);
+            carry /= LIMB_BASE as u128;
+        }
+
+        let mut result = BigUint{ limbs };
+        result.trim();
+        return result;
+    }
+}
+
+impl Ord for BigUint
+{
+    fn cmp(&self, other: &Self) -> Ordering
+    {
+        return self.limbs.len().cmp(&other.limbs.len())
+                               .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()));
+    }
+}
+
+impl PartialOrd for BigUint
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl fmt::Display for BigUint
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let mut limbs = self.limbs.iter().rev();
+        match limbs.next()
+        {
+            None       => return write!(f, "0"),
+            Some(last) => write!(f, "{}", last)?
+        }
+        for limb in limbs
+        {
+            write!(f, "{:09}", limb)?;
+        }
+        return Ok(());
+    }
+}
+
+// Same as Display, the limbs alone are hard to read
+impl fmt::Debug for BigUint
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        return fmt::Display::fmt(self, f);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_u128_round_trip()
+    {
+        for value in [0, 1, 999_999_999, 1_000_000_000, 26984457539, u64::MAX as u128, u128::MAX]
+        {
+            let big = BigUint::from(value);
+            assert_eq!(big.to_u128(), Some(value));
+            assert_eq!(big.to_string(), value.to_string());
+        }
+    }
+
+    #[test]
+    fn test_add_and_mul_match_u128()
+    {
+        let values = [0, 1, 7, 999_999_999, 1_000_000_001, 123_456_789_012_345, u64::MAX as u128];
+        for &a in &values
+        {
+            for &b in &values
+            {
+                let big_a = BigUint::from(a);
+                let big_b = BigUint::from(b);
+                assert_eq!((&big_a + &big_b).to_u128(), Some(a + b));
+                assert_eq!((&big_a * &big_b).to_u128(), Some(a * b));
+                assert_eq!(big_a.cmp(&big_b), a.cmp(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_beyond_u128()
+    {
+        let max = BigUint::from(u128::MAX);
+        let one = BigUint::from(1);
+
+        let sum = &max + &one;
+        assert_eq!(sum.to_u128(), None);
+        assert_eq!(format!("{:?}", sum), sum.to_string());
+        assert_eq!(sum.to_string(), "340282366920938463463374607431768211456");
+
+        // (2^128)^2 = 2^256
+        let square = &sum * &sum;
+        assert_eq!(square.to_string(), "115792089237316195423570985008687907853269984665640564039457584007913129639936");
+        assert_eq!(square.get_num_digits(), 78);
+        assert_eq!(square.rem_u32(1_000_000_007), 792_845_266);
+    }
+
+    #[test]
+    fn test_zero()
+    {
+        let zero = BigUint::zero();
+        assert_eq!(zero.to_string(), "0");
+        assert_eq!(zero.get_num_digits(), 1);
+        assert!((&zero * &BigUint::from(u128::MAX)).is_zero());
+    }
+}
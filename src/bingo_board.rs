@@ -1,10 +1,45 @@
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct BingoBoard
 {
-    cells: [BingoCell; 25],
+    // NOTE: ROW MAJOR!
+    cells: Vec<BingoCell>,
+    num_rows: usize,
+    num_cols: usize,
+    // Every winning pattern under the current rules, and the ones each cell
+    // belongs to, so completed patterns are found in O(1) per mark
+    patterns: Vec<WinPattern>,
+    cell_patterns: Vec<Vec<usize>>,
+    unmarked_sum: i32,
     bingo: bool
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinRule
+{
+    Rows,
+    Columns,
+    Diagonals, // Only on square boards
+    FourCorners,
+    Blackout,
+    // Row major mask of the cells to mark, same size as the board
+    Pattern(Vec<bool>)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BingoWin
+{
+    Row(usize),
+    Column(usize),
+    Diagonal,     // Top left to bottom right
+    AntiDiagonal, // Top right to bottom left
+    FourCorners,
+    Blackout,
+    Pattern(usize) // Index among the custom patterns of the rules
+}
+
+// The puzzle only counts rows and columns
+pub const DEFAULT_WIN_RULES: [WinRule; 2] = [WinRule::Rows, WinRule::Columns];
+
 #[derive(Default, Clone, Copy)]
 struct BingoCell
 {
@@ -12,91 +47,329 @@ struct BingoCell
     is_marked: bool
 }
 
+#[derive(Clone, Copy)]
+struct WinPattern
+{
+    win: BingoWin,
+    num_cells: usize,
+    num_marked: usize
+}
+
+impl WinRule
+{
+    // Parses a grid where 'X' is a cell of the pattern and anything else isn't, e.g.
+    // X...X
+    // .....
+    // ..X..
+    pub fn pattern_from_str(grid: &str) -> Self
+    {
+        let mask = grid.lines()
+                       .flat_map(|line| line.trim().chars())
+                       .map(|c| c == 'X')
+                       .collect();
+        return WinRule::Pattern(mask);
+    }
+
+    // A single word, patterns as their row major mask, e.g. "pattern:X...X"
+    pub fn render(&self) -> String
+    {
+        return match self
+        {
+            WinRule::Rows        => "rows".to_string(),
+            WinRule::Columns     => "columns".to_string(),
+            WinRule::Diagonals   => "diagonals".to_string(),
+            WinRule::FourCorners => "corners".to_string(),
+            WinRule::Blackout    => "blackout".to_string(),
+            WinRule::Pattern(mask) =>
+            {
+                let cells: String = mask.iter().map(|&is_set| if is_set {'X'} else {'.'}).collect();
+                format!("pattern:{}", cells)
+            }
+        };
+    }
+
+    // Reads back the output of `render`
+    pub fn parse(entry: &str) -> Result<Self, String>
+    {
+        if let Some(cells) = entry.strip_prefix("pattern:")
+        {
+            return Ok( WinRule::Pattern(cells.chars().map(|c| c == 'X').collect()) );
+        }
+        return match entry
+        {
+            "rows"      => Ok(WinRule::Rows),
+            "columns"   => Ok(WinRule::Columns),
+            "diagonals" => Ok(WinRule::Diagonals),
+            "corners"   => Ok(WinRule::FourCorners),
+            "blackout"  => Ok(WinRule::Blackout),
+             _          => Err(format!("Unknown win rule '{}'", entry))
+        };
+    }
+}
+
 impl BingoBoard
 {
+    pub fn new(num_rows: usize, num_cols: usize) -> Self
+    {
+        let mut board = Self
+        {
+            cells: vec![BingoCell::default(); num_rows * num_cols],
+            num_rows,
+            num_cols,
+            patterns: Vec::new(),
+            cell_patterns: Vec::new(),
+            unmarked_sum: 0,
+            bingo: false
+        };
+        board.apply_win_rules(&DEFAULT_WIN_RULES);
+        return board;
+    }
+
+    // Custom patterns must have a cell for every cell of the board
+    pub fn check_win_rules(&self, rules: &[WinRule]) -> Result<(), String>
+    {
+        for rule in rules
+        {
+            if let WinRule::Pattern(mask) = rule
+            {
+                if mask.len() != self.cells.len()
+                {
+                    return Err(format!("Pattern of {} cells on a {}x{} board", mask.len(), self.num_cols, self.num_rows));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Fails without changing anything if the rules don't fit the board
+    pub fn set_win_rules(&mut self, rules: &[WinRule]) -> Result<(), String>
+    {
+        self.check_win_rules(rules)?;
+        self.apply_win_rules(rules);
+        return Ok(());
+    }
+
+    fn apply_win_rules(&mut self, rules: &[WinRule])
+    {
+        self.patterns.clear();
+        self.cell_patterns = vec![Vec::new(); self.cells.len()];
+
+        let rows = self.num_rows;
+        let cols = self.num_cols;
+        let mut custom_idx = 0;
+
+        for rule in rules
+        {
+            match rule
+            {
+                WinRule::Rows =>
+                {
+                    for y in 0..rows
+                    {
+                        self.add_pattern(BingoWin::Row(y), (0..cols).map(|x| y * cols + x).collect());
+                    }
+                },
+                WinRule::Columns =>
+                {
+                    for x in 0..cols
+                    {
+                        self.add_pattern(BingoWin::Column(x), (0..rows).map(|y| y * cols + x).collect());
+                    }
+                },
+                WinRule::Diagonals =>
+                {
+                    if rows != cols || rows == 0 { continue; }
+                    self.add_pattern(BingoWin::Diagonal,     (0..rows).map(|i| i * cols + i).collect());
+                    self.add_pattern(BingoWin::AntiDiagonal, (0..rows).map(|i| i * cols + cols - 1 - i).collect());
+                },
+                WinRule::FourCorners =>
+                {
+                    if self.cells.is_empty() { continue; }
+                    let mut corners = vec![0, cols - 1, (rows - 1) * cols, rows * cols - 1];
+                    corners.sort_unstable();
+                    corners.dedup(); // Boards with a single row or column
+                    self.add_pattern(BingoWin::FourCorners, corners);
+                },
+                WinRule::Blackout =>
+                {
+                    self.add_pattern(BingoWin::Blackout, (0..self.cells.len()).collect());
+                },
+                WinRule::Pattern(mask) =>
+                {
+                    let cells = (0..mask.len()).filter(|&i| mask[i]).collect();
+                    self.add_pattern(BingoWin::Pattern(custom_idx), cells);
+                    custom_idx += 1;
+                }
+            }
+        }
+
+        // Marks made under the old rules may already complete a new pattern
+        self.bingo = self.patterns.iter().any(|p| p.num_marked == p.num_cells);
+    }
+
+    fn add_pattern(&mut self, win: BingoWin, cells: Vec<usize>)
+    {
+        if cells.is_empty() { return; }
+
+        let pattern_idx = self.patterns.len();
+        let num_marked  = cells.iter().filter(|&&i| self.cells[i].is_marked).count();
+        self.patterns.push( WinPattern{ win, num_cells: cells.len(), num_marked } );
+
+        for i in cells
+        {
+            self.cell_patterns[i].push(pattern_idx);
+        }
+    }
+
     pub fn get_bingo_state(&self) -> bool { return self.bingo }
 
-    pub fn set_cell_at(&mut self, x: usize, y: usize, val: i32)
+    // (width, height)
+    pub fn get_size(&self) -> (usize, usize) { (self.num_cols, self.num_rows) }
+
+    pub fn get_num_cells(&self) -> usize { self.cells.len() }
+    pub fn get_cell_value(&self, i: usize) -> i32
     {
-        self.cells[x * 5 + y] = BingoCell{value: val, is_marked: false};
+        assert!(i < self.cells.len());
+        return self.cells[i].value;
     }
 
-    pub fn get_unmarked_sum(&self) -> i32
+    pub fn set_cell_at(&mut self, x: usize, y: usize, val: i32)
     {
-        let mut sum = 0;
-        for cell in self.cells
+        assert!(x < self.num_cols && y < self.num_rows);
+
+        let i = y * self.num_cols + x;
+        if self.cells[i].is_marked
         {
-            if !cell.is_marked { sum += cell.value; }
+            for &p in &self.cell_patterns[i]
+            {
+                self.patterns[p].num_marked -= 1;
+            }
+        }
+        else
+        {
+            self.unmarked_sum -= self.cells[i].value;
         }
 
-        return sum;
+        self.cells[i]      = BingoCell{value: val, is_marked: false};
+        self.unmarked_sum += val;
     }
 
+    pub fn get_unmarked_sum(&self) -> i32 { self.unmarked_sum }
+
     pub fn check_and_mark(&mut self, number: i32) -> bool
+    {
+        return self.mark(number).is_some();
+    }
+
+    // Returns the pattern completed by this number, if any.
+    // Scans the whole board, use `mark_cell` when the cell is already known.
+    pub fn mark(&mut self, number: i32) -> Option<BingoWin>
     {
         for i in 0..self.cells.len()
         {
             if self.cells[i].value == number
             {
-                self.cells[i].is_marked = true;
-                if self.check_bingo(i)
+                let win = self.mark_cell(i);
+                if win.is_some()
                 {
                     // BINGO!
-                    return true;
+                    return win;
                 }
             }
         }
 
-        return false;
+        return None;
     }
 
-    fn check_bingo(&mut self, cell_idx: usize) -> bool
+    // If several patterns are completed at once, the first one in the rules wins
+    pub fn mark_cell(&mut self, cell_idx: usize) -> Option<BingoWin>
     {
-        let x = cell_idx / 5;
-        let y = cell_idx - x * 5;
+        if self.cells[cell_idx].is_marked { return None; }
 
-        self.bingo = self.check_bingo_row(y) ||
-                     self.check_bingo_col(x);
-        return self.bingo
+        self.cells[cell_idx].is_marked = true;
+        self.unmarked_sum -= self.cells[cell_idx].value;
+
+        let mut win = None;
+        for &p in &self.cell_patterns[cell_idx]
+        {
+            let pattern = &mut self.patterns[p];
+            pattern.num_marked += 1;
+
+            if win.is_none() && pattern.num_marked == pattern.num_cells
+            {
+                win = Some(pattern.win);
+            }
+        }
+
+        self.bingo |= win.is_some();
+        return win;
     }
 
-    fn check_bingo_col(&self, col_idx: usize) -> bool
+    pub fn is_cell_marked(&self, i: usize) -> bool
     {
-        return self.cells[col_idx*5+0].is_marked &&
-               self.cells[col_idx*5+1].is_marked &&
-               self.cells[col_idx*5+2].is_marked &&
-               self.cells[col_idx*5+3].is_marked &&
-               self.cells[col_idx*5+4].is_marked;
+        assert!(i < self.cells.len());
+        return self.cells[i].is_marked;
     }
 
-    fn check_bingo_row(&self, row_idx: usize) -> bool
+    // One line per row, marked cells followed by '!'
+    pub fn render(&self) -> String
     {
-        return self.cells[0*5+row_idx].is_marked &&
-               self.cells[1*5+row_idx].is_marked &&
-               self.cells[2*5+row_idx].is_marked &&
-               self.cells[3*5+row_idx].is_marked &&
-               self.cells[4*5+row_idx].is_marked;
+        let mut result = String::new();
+        for row in self.cells.chunks(self.num_cols.max(1))
+        {
+            let entries: Vec<String> = row.iter()
+                                          .map(|cell| format!("{}{}", cell.value, if cell.is_marked {"!"} else {""}))
+                                          .collect();
+            result += &entries.join(" ");
+            result += "\n";
+        }
+        return result;
     }
 
     pub fn print(&self)
     {
-        for x in 0..5
-        {
-            println!("{}{} {}{} {}{} {}{} {}{}",
-                     self.cells[0*5+x].value,
-                     if self.cells[0*5+x].is_marked {"!"} else {""},
+        print!("{}", self.render());
+    }
 
-                     self.cells[1*5+x].value,
-                     if self.cells[1*5+x].is_marked {"!"} else {""},
+    // Reads back the output of `print`, marks included.
+    // Win rules are reset to the default ones.
+    pub fn parse(block: &str) -> Result<Self, String>
+    {
+        let mut rows: Vec<Vec<(i32, bool)>> = Vec::new();
+        for line in block.lines()
+        {
+            if line.trim().is_empty() { continue; }
 
-                     self.cells[2*5+x].value,
-                     if self.cells[2*5+x].is_marked {"!"} else {""},
+            let mut row = Vec::new();
+            for entry in line.split_whitespace()
+            {
+                let (value, is_marked) = match entry.strip_suffix('!')
+                {
+                    Some(value) => (value, true),
+                    None        => (entry, false)
+                };
+                let value = value.parse::<i32>().map_err(|why| format!("Invalid cell '{}': {}", entry, why))?;
+                row.push((value, is_marked));
+            }
+            rows.push(row);
+        }
 
-                     self.cells[3*5+x].value,
-                     if self.cells[3*5+x].is_marked {"!"} else {""},
+        let num_rows = rows.len();
+        let num_cols = rows.first().map(|row| row.len()).unwrap_or(0);
+        if rows.iter().any(|row| row.len() != num_cols)
+        {
+            return Err("Ragged bingo board!".to_string());
+        }
 
-                     self.cells[4*5+x].value,
-                     if self.cells[4*5+x].is_marked {"!"} else {""});
+        let mut board = Self::new(num_rows, num_cols);
+        for (y, row) in rows.iter().enumerate()
+        {
+            for (x, &(value, is_marked)) in row.iter().enumerate()
+            {
+                board.set_cell_at(x, y, value);
+                if is_marked { board.mark_cell(y * num_cols + x); }
+            }
         }
+        return Ok(board);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,456 @@
+use std::
+{
+    collections::HashMap,
+    fs,
+    io
+};
+
+use crate::
+{
+    bingo_board::*,
+    input_parser::*
+};
+
+pub struct BingoGame
+{
+    numbers: Vec<i32>,
+    boards: Vec<BingoBoard>,
+    // How many numbers have been drawn so far
+    turn: usize,
+    // Shared by every board
+    rules: Vec<WinRule>,
+    // Every (board, cell) holding a given value
+    cell_index: HashMap<i32, Vec<(usize, usize)>>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardResult
+{
+    pub board_idx: usize,
+    pub turn: usize, // 1 is the first number drawn
+    pub number: i32,
+    pub win: BingoWin,
+    pub score: i32
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GameReport
+{
+    // In winning order
+    pub winners: Vec<BoardResult>,
+    pub never_won: Vec<usize>
+}
+
+impl BingoGame
+{
+    pub fn new(numbers: Vec<i32>, boards: Vec<BingoBoard>) -> Self
+    {
+        let mut cell_index: HashMap<i32, Vec<(usize, usize)>> = HashMap::new();
+        for (board_idx, board) in boards.iter().enumerate()
+        {
+            for cell_idx in 0..board.get_num_cells()
+            {
+                cell_index.entry( board.get_cell_value(cell_idx) )
+                          .or_default()
+                          .push((board_idx, cell_idx));
+            }
+        }
+
+        return Self{ numbers, boards, turn: 0, rules: DEFAULT_WIN_RULES.to_vec(), cell_index };
+    }
+
+    pub fn new_from_input(input: &String) -> Result<Self, String>
+    {
+        return Ok( Self::new( parse_drawn_nums(input), parse_boards(input)? ) );
+    }
+
+    // Boards of different sizes can't share a custom pattern.
+    // Fails without changing any board.
+    pub fn set_win_rules(&mut self, rules: &[WinRule]) -> Result<(), String>
+    {
+        for (board_idx, board) in self.boards.iter().enumerate()
+        {
+            board.check_win_rules(rules).map_err(|why| format!("Board {}: {}", board_idx, why))?;
+        }
+        for board in &mut self.boards
+        {
+            board.set_win_rules(rules)?;
+        }
+        self.rules = rules.to_vec();
+        return Ok(());
+    }
+
+    pub fn get_turn(&self) -> usize { self.turn }
+    pub fn get_win_rules(&self) -> &[WinRule] { &self.rules }
+    pub fn get_boards(&self) -> &[BingoBoard] { &self.boards }
+
+    // Draws the next number and returns the boards it made win, or None if
+    // there are no numbers left.
+    // Boards stop playing as soon as they win, and boards winning on the same
+    // turn are listed in input order.
+    pub fn draw(&mut self) -> Option<Vec<BoardResult>>
+    {
+        let num = *self.numbers.get(self.turn)?;
+        self.turn += 1;
+
+        let mut winners = Vec::new();
+        let hits = match self.cell_index.get(&num)
+        {
+            Some(hits) => hits,
+            None       => return Some(winners)
+        };
+
+        // Hits are sorted by board, so winners keep the input order
+        for &(board_idx, cell_idx) in hits
+        {
+            let board = &mut self.boards[board_idx];
+            if board.get_bingo_state() { continue; }
+
+            if let Some(win) = board.mark_cell(cell_idx)
+            {
+                winners.push( BoardResult
+                {
+                    board_idx,
+                    turn: self.turn,
+                    number: num,
+                    win,
+                    score: num * board.get_unmarked_sum()
+                });
+            }
+        }
+        return Some(winners);
+    }
+
+    // Draws every remaining number.
+    // On a restored game, boards that won before the snapshot are in neither list.
+    pub fn play(&mut self) -> GameReport
+    {
+        let already_won: Vec<bool> = self.boards.iter()
+                                                .map(|board| board.get_bingo_state())
+                                                .collect();
+
+        let mut report = GameReport::default();
+        while let Some(winners) = self.draw()
+        {
+            report.winners.extend(winners);
+        }
+
+        report.never_won = (0..self.boards.len()).filter(|&i| !already_won[i] && !self.boards[i].get_bingo_state())
+                                                 .collect();
+        return report;
+    }
+
+    // Snapshot format:
+    //  - The whole draw, like the puzzle input
+    //  - "drawn N", how many of those numbers were already drawn
+    //  - "rules" followed by every win rule as rendered by `WinRule::render`
+    //  - Every board as printed by `BingoBoard::print`, after an empty line
+    // Whether a board has won follows from its marks and the rules, as boards
+    // stop being marked once they win.
+    pub fn save(&self) -> String
+    {
+        let numbers: Vec<String> = self.numbers.iter().map(|num| num.to_string()).collect();
+        let rules:   Vec<String> = self.rules.iter().map(|rule| rule.render()).collect();
+
+        let mut snapshot = format!("{}\ndrawn {}\nrules {}\n", numbers.join(","), self.turn, rules.join(" "));
+        for board in &self.boards
+        {
+            snapshot += "\n";
+            snapshot += &board.render();
+        }
+        return snapshot;
+    }
+
+    // Snapshots without a rules line use the default rules
+    pub fn load(snapshot: &str) -> Result<Self, String>
+    {
+        let mut lines = snapshot.lines().peekable();
+
+        let numbers_line = lines.next().unwrap_or_default().trim();
+        let mut numbers  = Vec::new();
+        if !numbers_line.is_empty()
+        {
+            for entry in numbers_line.split(',')
+            {
+                numbers.push( entry.trim().parse::<i32>().map_err(|why| format!("Invalid number '{}': {}", entry, why))? );
+            }
+        }
+
+        let turn_line = lines.next().unwrap_or_default();
+        let turn = turn_line.strip_prefix("drawn ")
+                            .and_then(|count| count.trim().parse::<usize>().ok())
+                            .ok_or(format!("Invalid drawn count '{}'", turn_line))?;
+        if turn > numbers.len()
+        {
+            return Err(format!("Drawn {} numbers out of {}", turn, numbers.len()));
+        }
+
+        let mut rules = DEFAULT_WIN_RULES.to_vec();
+        if let Some(rules_line) = lines.peek().and_then(|line| line.strip_prefix("rules"))
+        {
+            rules = rules_line.split_whitespace()
+                              .map(WinRule::parse)
+                              .collect::<Result<Vec<WinRule>, String>>()?;
+            lines.next();
+        }
+
+        let rest: Vec<&str> = lines.collect();
+        let mut boards = Vec::new();
+        for block in rest.join("\n").split("\n\n")
+        {
+            if block.trim().is_empty() { continue; }
+            boards.push( BingoBoard::parse(block)? );
+        }
+
+        let mut game = Self::new(numbers, boards);
+        game.set_win_rules(&rules)?;
+        game.turn    = turn;
+        return Ok(game);
+    }
+
+    pub fn save_to_file(&self, path: &str) -> io::Result<()>
+    {
+        return fs::write(path, self.save());
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Self, String>
+    {
+        let snapshot = parse_input_at(path).map_err(|why| why.to_string())?;
+        return Self::load(&snapshot);
+    }
+}
+
+impl GameReport
+{
+    pub fn get_first_winner(&self) -> Option<&BoardResult> { self.winners.first() }
+    pub fn get_last_winner(&self)  -> Option<&BoardResult> { self.winners.last() }
+
+    pub fn print(&self)
+    {
+        for (rank, result) in self.winners.iter().enumerate()
+        {
+            println!("#{} board {}: turn {}, number {}, {:?}, score {}",
+                     rank + 1,
+                     result.board_idx,
+                     result.turn,
+                     result.number,
+                     result.win,
+                     result.score);
+        }
+        for board_idx in &self.never_won
+        {
+            println!("board {}: never won", board_idx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_example_report()
+    {
+        let input = parse_input_at("src/inputs/day_4_example.txt").unwrap();
+        let report = BingoGame::new_from_input(&input).unwrap().play();
+
+        assert_eq!(report.winners.len(), 3);
+        assert!(report.never_won.is_empty());
+
+        assert_eq!(report.winners[0], BoardResult{ board_idx: 2, turn: 12, number: 24, win: BingoWin::Row(0),    score: 4512 });
+        assert_eq!(report.winners[1], BoardResult{ board_idx: 0, turn: 14, number: 16, win: BingoWin::Row(2),    score: 2192 });
+        assert_eq!(report.winners[2], BoardResult{ board_idx: 1, turn: 15, number: 13, win: BingoWin::Column(2), score: 1924 });
+    }
+
+    #[test]
+    fn test_boards_that_never_win()
+    {
+        let input = "1,2,3\n\n1 2\n3 4\n\n5 6\n7 8\n\n4 3\n2 1\n".to_string();
+        let report = BingoGame::new_from_input(&input).unwrap().play();
+
+        assert_eq!(report.winners.len(), 2);
+        assert_eq!(report.winners[0].board_idx, 0);
+        assert_eq!(report.winners[1].board_idx, 2);
+        assert_eq!(report.never_won, vec![1]);
+    }
+
+    #[test]
+    fn test_empty_draw()
+    {
+        let input = "\n\n1 2\n3 4\n".to_string();
+        let report = BingoGame::new_from_input(&input).unwrap().play();
+
+        assert!(report.get_first_winner().is_none());
+        assert_eq!(report.never_won, vec![0]);
+    }
+
+    // Plays the same game the slow way, scanning every board for every number
+    fn play_by_scanning(numbers: &[i32], boards: &mut [BingoBoard]) -> Vec<(usize, i32)>
+    {
+        let mut winners = Vec::new();
+        for &num in numbers
+        {
+            for (board_idx, board) in boards.iter_mut().enumerate()
+            {
+                if !board.get_bingo_state() && board.check_and_mark(num)
+                {
+                    winners.push( (board_idx, num * board.get_unmarked_sum()) );
+                }
+            }
+        }
+        return winners;
+    }
+
+    #[test]
+    fn test_many_boards()
+    {
+        let num_boards = 10_000;
+
+        let mut boards = Vec::with_capacity(num_boards);
+        for b in 0..num_boards
+        {
+            let mut board = BingoBoard::new(5, 5);
+            for i in 0..25
+            {
+                board.set_cell_at(i % 5, i / 5, ((b * 7919 + i * 104729) % 100) as i32);
+            }
+            boards.push(board);
+        }
+        let numbers: Vec<i32> = (0..100).map(|i| (i * 37) % 100).collect();
+
+        let expected = play_by_scanning(&numbers, &mut boards.clone());
+        let report   = BingoGame::new(numbers, boards).play();
+
+        let winners: Vec<(usize, i32)> = report.winners.iter()
+                                                       .map(|result| (result.board_idx, result.score))
+                                                       .collect();
+        assert_eq!(winners, expected);
+        assert_eq!(report.winners.len() + report.never_won.len(), num_boards);
+    }
+
+    #[test]
+    fn test_unmarked_sum_tracking()
+    {
+        let mut board = BingoBoard::new(2, 2);
+        board.set_cell_at(0, 0, 1);
+        board.set_cell_at(1, 0, 2);
+        board.set_cell_at(0, 1, 3);
+        board.set_cell_at(1, 1, 4);
+        assert_eq!(board.get_unmarked_sum(), 10);
+
+        assert_eq!(board.mark_cell(3), None);
+        assert_eq!(board.mark_cell(3), None); // Marking twice changes nothing
+        assert_eq!(board.get_unmarked_sum(), 6);
+
+        assert_eq!(board.mark_cell(1), Some(BingoWin::Column(1)));
+        assert_eq!(board.get_unmarked_sum(), 4);
+    }
+
+    #[test]
+    fn test_save_and_resume()
+    {
+        let input = parse_input_at("src/inputs/day_4_example.txt").unwrap();
+        let full_report = BingoGame::new_from_input(&input).unwrap().play();
+
+        let mut game = BingoGame::new_from_input(&input).unwrap();
+        for _ in 0..13
+        {
+            game.draw();
+        }
+        let snapshot = game.save();
+
+        let mut restored = BingoGame::load(&snapshot).unwrap();
+        assert_eq!(restored.get_turn(), 13);
+        assert_eq!(restored.save(), snapshot);
+
+        // The third board already won on turn 12
+        let report = restored.play();
+        assert_eq!(report.winners, full_report.winners[1..].to_vec());
+        assert!(report.never_won.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_resume_with_rules()
+    {
+        let input = "1,5,9,2,3,4\n\n1 2 3\n4 5 6\n7 8 9\n".to_string();
+        let rules = [WinRule::Diagonals, WinRule::Rows, WinRule::pattern_from_str("X.X\n...\nX.X")];
+
+        let mut game = BingoGame::new_from_input(&input).unwrap();
+        game.set_win_rules(&rules).unwrap();
+        for _ in 0..3
+        {
+            game.draw();
+        }
+        assert!(game.get_boards()[0].get_bingo_state());
+
+        let mut restored = BingoGame::load(&game.save()).unwrap();
+        assert_eq!(restored.get_win_rules(), &rules);
+        assert!(restored.get_boards()[0].get_bingo_state());
+        assert_eq!(restored.save(), game.save());
+
+        // The row completed on turn 5 doesn't count, the board already won
+        let report = restored.play();
+        assert!(report.winners.is_empty());
+        assert!(report.never_won.is_empty());
+    }
+
+    #[test]
+    fn test_pattern_on_mixed_board_sizes()
+    {
+        let input = "1,2,3\n\n1 2\n3 4\n\n1 2 3\n4 5 6\n7 8 9\n".to_string();
+        let mut game = BingoGame::new_from_input(&input).unwrap();
+
+        let corners = WinRule::pattern_from_str("X.\n.X");
+        assert_eq!(game.set_win_rules(&[WinRule::Rows, corners]),
+                   Err("Board 1: Pattern of 4 cells on a 3x3 board".to_string()));
+        assert_eq!(game.get_win_rules(), &DEFAULT_WIN_RULES);
+
+        assert!(game.set_win_rules(&[WinRule::Blackout]).is_ok());
+    }
+
+    #[test]
+    fn test_snapshot_format()
+    {
+        let input = "1,2,3\n\n1 2\n3 4\n".to_string();
+        let mut game = BingoGame::new_from_input(&input).unwrap();
+        game.draw();
+
+        assert_eq!(game.save(), "1,2,3\ndrawn 1\nrules rows columns\n\n1! 2\n3 4\n");
+    }
+
+    #[test]
+    fn test_load_regression_position()
+    {
+        // One number away from a column
+        let snapshot = "5,7,9\ndrawn 0\n\n\
+                        1! 2 3\n\
+                        4! 5 6\n\
+                        7 8 9\n";
+        let mut game = BingoGame::load(snapshot).unwrap();
+        let report   = game.play();
+
+        assert_eq!(report.winners.len(), 1);
+        assert_eq!(report.winners[0].turn, 2);
+        assert_eq!(report.winners[0].win, BingoWin::Column(0));
+        assert_eq!(report.winners[0].score, 7 * (2+3+6+8+9));
+    }
+
+    #[test]
+    fn test_load_invalid_snapshots()
+    {
+        assert!(BingoGame::load("1,2,x\ndrawn 0\n").is_err());
+        assert!(BingoGame::load("1,2\ndrawn 3\n").is_err());
+        assert!(BingoGame::load("1,2\nturn 1\n").is_err());
+        assert!(BingoGame::load("1,2\ndrawn 1\n\n1 2\n3\n").is_err());
+        assert!(BingoGame::load("1,2\ndrawn 1\nrules rows sideways\n").is_err());
+    }
+
+    // The snapshot itself is covered by `test_save_and_resume`, no need to
+    // write any file
+    #[test]
+    fn test_load_missing_file()
+    {
+        assert!(BingoGame::load_from_file("src/inputs/missing.txt").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
 pub struct Coordinate
 {
     // No X for now
@@ -4,84 +4,151 @@ use crate::
     coordinates::Coordinate
 };
 
-pub fn day_2_1() -> i32
+pub fn day_2_1() -> Result<i32, String>
 {
     let input = parse_input_at("src/inputs/day_2_input.txt");
     return match input
     {
-        Err(_)    => return 0,
+        Err(why)  => Err(why.to_string()),
         Ok(input) => compute_position_product_from_input(input)
     };
 }
 
-pub fn day_2_2() -> i32
+pub fn day_2_2() -> Result<i32, String>
 {
     let input = parse_input_at("src/inputs/day_2_input.txt");
     return match input
     {
-        Err(_)    => return 0,
+        Err(why)  => Err(why.to_string()),
         Ok(input) => compute_position_product_from_input_2(input)
     };
 }
 
-fn compute_position_product_from_input(input: String) -> i32
+fn compute_position_product_from_input(input: String) -> Result<i32, String>
 {
-    let lines = input.split("\n");
+    return compute_position_product(&input, MovementModel::Direct);
+}
 
-    let mut position = Coordinate{y:0, z:0};
+fn compute_position_product_from_input_2(input: String) -> Result<i32, String>
+{
+    return compute_position_product(&input, MovementModel::Aim);
+}
 
-    for line in lines
-    {
-        position += get_movement_from_input_entry(line);
-    }
+fn compute_position_product(input: &str, model: MovementModel) -> Result<i32, String>
+{
+    let commands = parse_commands(input).map_err(|why| format!("Invalid course: {}", why))?;
 
-    // We're using DEPTH, so we have to negate Y
-    return -position.y * position.z;
+    let mut submarine = Submarine::new(model);
+    submarine.run(&commands);
+    return Ok(submarine.get_position_product());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command
+{
+    Forward(i32),
+    Up(i32),
+    Down(i32)
 }
 
-fn compute_position_product_from_input_2(input: String) -> i32
+fn parse_command(entry: &str) -> Result<Command, String>
 {
-    let lines = input.lines();
+    let mut words = entry.split_whitespace();
+
+    let name = words.next().unwrap_or_default();
+    let dist = words.next()
+                    .ok_or(format!("Missing distance in '{}'", entry))?
+                    .parse::<i32>()
+                    .map_err(|why| format!("Invalid distance in '{}': {}", entry, why))?;
 
-    let mut aim      = 0;
-    let mut position = Coordinate{y:0, z:0};
+    if words.next().is_some() { return Err(format!("Unexpected trailing data in '{}'", entry)); }
+
+    return match name
+    {
+        "forward" => Ok(Command::Forward(dist)),
+        "up"      => Ok(Command::Up(dist)),
+        "down"    => Ok(Command::Down(dist)),
+         _        => Err(format!("Unknown command '{}'", name))
+    };
+}
 
-    for line in lines
+fn parse_commands(input: &str) -> Result<Vec<Command>, String>
+{
+    let mut commands = Vec::new();
+    for (i, line) in input.lines().enumerate()
     {
-        let (dir, dist) = parse_input_entry(line);
-        aim        -= dir.y * dist;
-        position.y -= dir.z * aim * dist;
-        position.z += dir.z * dist;
+        if line.trim().is_empty() { continue; }
+
+        let command = parse_command(line).map_err(|why| format!("Line {}: {}", i+1, why))?;
+        commands.push(command);
     }
+    return Ok(commands);
+}
 
-    // We're using DEPTH, so we have to negate Y
-    return -position.y * position.z;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MovementModel
+{
+    // Up and down change the depth directly
+    Direct,
+    // Up and down change the aim, and moving forward follows it
+    Aim
 }
 
-fn get_movement_from_input_entry(entry: &str) -> Coordinate
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct SubmarineState
 {
-    let (dir, dist) = parse_input_entry(entry);
-    return dir * dist;
+    position: Coordinate,
+    aim: i32
 }
 
-fn parse_input_entry(entry: &str) -> (Coordinate, i32)
+struct Submarine
 {
-    let mut words = entry.split_whitespace();
+    model: MovementModel,
+    state: SubmarineState,
+    // Every state the submarine went through, starting with the initial one
+    trajectory: Vec<SubmarineState>
+}
 
-    let dir = match words.next().unwrap_or("")
+impl Submarine
+{
+    pub fn new(model: MovementModel) -> Self
     {
-        "forward" => Coordinate{y:0,  z:1},
-        "up"      => Coordinate{y:1,  z:0},
-        "down"    => Coordinate{y:-1, z:0},
-        _         => Coordinate{y:0,  z:0}
-    };
+        let state = SubmarineState::default();
+        return Self{ model, state, trajectory: vec![state] };
+    }
 
-    let dist = words.next()
-                    .unwrap_or("0")
-                    .parse::<i32>()
-                    .unwrap_or(0);
+    pub fn run(&mut self, commands: &[Command])
+    {
+        for command in commands
+        {
+            self.execute(*command);
+        }
+    }
 
-    return (dir, dist);
+    pub fn execute(&mut self, command: Command)
+    {
+        let state = &mut self.state;
+
+        // We're using DEPTH, so going down means a negative Y
+        match (self.model, command)
+        {
+            (MovementModel::Direct, Command::Forward(dist)) => state.position.z += dist,
+            (MovementModel::Direct, Command::Up(dist))      => state.position.y += dist,
+            (MovementModel::Direct, Command::Down(dist))    => state.position.y -= dist,
+
+            (MovementModel::Aim,    Command::Forward(dist)) => state.position += Coordinate{y: -state.aim * dist, z: dist},
+            (MovementModel::Aim,    Command::Up(dist))      => state.aim -= dist,
+            (MovementModel::Aim,    Command::Down(dist))    => state.aim += dist
+        }
+
+        self.trajectory.push(*state);
+    }
+
+    pub fn get_state(&self)      -> SubmarineState   { self.state }
+    pub fn get_trajectory(&self) -> &[SubmarineState] { &self.trajectory }
+
+    // We're using DEPTH, so we have to negate Y
+    pub fn get_position_product(&self) -> i32 { -self.state.position.y * self.state.position.z }
 }
 
 #[cfg(test)]
@@ -99,53 +166,63 @@ mod tests
         let input = input.unwrap();
 
         let result = compute_position_product_from_input(input);
-        assert_eq!(result, 150);
+        assert_eq!(result, Ok(150));
     }
 
     #[test]
     fn test_no_distance()
     {
-        let entry = "forward";
-        let movement = get_movement_from_input_entry(entry);
-
-        let expected = Coordinate{y:0, z:0};
-
-        assert_eq!(movement, expected);
+        assert!(parse_command("forward").is_err());
     }
 
     #[test]
     fn test_no_direction()
     {
-        let entry = "5";
-        let movement = get_movement_from_input_entry(entry);
-
-        let expected = Coordinate{y:0, z:0};
-
-        assert_eq!(movement, expected);
+        assert!(parse_command("5").is_err());
     }
 
     #[test]
-    fn test_get_movement_from_input_entry()
+    fn test_unknown_command()
     {
-        let entry = "forward 2";
-        let movement = get_movement_from_input_entry(entry);
-
-        let expected = Coordinate{y:0, z:2};
+        assert!(parse_command("backward 5").is_err());
+        assert!(parse_commands("forward 5\nsideways 2").is_err());
+        assert_eq!(compute_position_product("forward 5\nsideways 2", MovementModel::Direct),
+                   Err("Invalid course: Line 2: Unknown command 'sideways'".to_string()));
+    }
 
-        assert_eq!(movement, expected);
+    #[test]
+    fn test_parse_command()
+    {
+        assert_eq!(parse_command("forward 2"), Ok(Command::Forward(2)));
+        assert_eq!(parse_command("up 3"),      Ok(Command::Up(3)));
+        assert_eq!(parse_command("down 8"),    Ok(Command::Down(8)));
     }
 
     #[test]
-    fn test_parse_input_entry()
+    fn test_parse_example_commands()
     {
-        let entry = "forward 2";
-        let (dir, dist) = parse_input_entry(entry);
+        let input = parse_input_at("src/inputs/day_2_example.txt").unwrap();
+        let commands = parse_commands(&input).unwrap();
 
-        let expected_dir  = Coordinate{y:0, z:1};
-        let expected_dist = 2;
+        assert_eq!(commands.len(), 6);
+        assert_eq!(commands[0], Command::Forward(5));
+        assert_eq!(commands[5], Command::Forward(2));
+    }
 
-        assert_eq!(dir, expected_dir);
-        assert_eq!(dist, expected_dist);
+    #[test]
+    fn test_direct_trajectory()
+    {
+        let mut submarine = Submarine::new(MovementModel::Direct);
+        submarine.run(&[Command::Forward(5), Command::Down(5), Command::Up(3)]);
+
+        let positions: Vec<Coordinate> = submarine.get_trajectory()
+                                                  .iter()
+                                                  .map(|state| state.position)
+                                                  .collect();
+        assert_eq!(positions, vec![Coordinate{y:0,  z:0},
+                                   Coordinate{y:0,  z:5},
+                                   Coordinate{y:-5, z:5},
+                                   Coordinate{y:-2, z:5}]);
     }
 
     // PUZZLE 2
@@ -158,6 +235,34 @@ mod tests
         let input = input.unwrap();
 
         let result = compute_position_product_from_input_2(input);
-        assert_eq!(result, 900);
+        assert_eq!(result, Ok(900));
+    }
+
+    #[test]
+    fn test_aim_trajectory()
+    {
+        let input = parse_input_at("src/inputs/day_2_example.txt").unwrap();
+        let commands = parse_commands(&input).unwrap();
+
+        let mut submarine = Submarine::new(MovementModel::Aim);
+        submarine.run(&commands);
+
+        let trajectory = submarine.get_trajectory();
+        assert_eq!(trajectory.len(), commands.len() + 1);
+
+        // forward 5, down 5, forward 8
+        assert_eq!(trajectory[1], SubmarineState{ position: Coordinate{y:0,   z:5},  aim: 0 });
+        assert_eq!(trajectory[2], SubmarineState{ position: Coordinate{y:0,   z:5},  aim: 5 });
+        assert_eq!(trajectory[3], SubmarineState{ position: Coordinate{y:-40, z:13}, aim: 5 });
+
+        assert_eq!(submarine.get_state(), trajectory[6]);
+        assert_eq!(submarine.get_state().aim, 10);
+    }
+
+    #[test]
+    fn test_full_input()
+    {
+        assert_eq!(day_2_1(), Ok(1507611));
+        assert_eq!(day_2_2(), Ok(1880593125));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,502 @@
+use std::
+{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap}
+};
+
+use crate::input_parser::parse_input_at;
+
+const HALLWAY_LEN: usize = 11;
+const NUM_ROOMS:   usize = 4;
+const MAX_DEPTH:   usize = 4;
+const EMPTY:       char  = '.';
+
+// Rows hidden by the fold of the diagram, inserted between the two original ones
+const FOLDED_ROWS: [[char; NUM_ROOMS]; 2] = [['D', 'C', 'B', 'A'],
+                                             ['D', 'B', 'A', 'C']];
+
+pub fn day_23_1(input_path: &str) -> Result<usize, String>
+{
+    let burrow = Burrow::new_from_file(input_path)?;
+    return find_minimal_energy(&burrow).ok_or("The burrow can't be organised!".to_string());
+}
+
+pub fn day_23_2(input_path: &str) -> Result<usize, String>
+{
+    let burrow = Burrow::new_from_file(input_path)?.unfold()?;
+    return find_minimal_energy(&burrow).ok_or("The burrow can't be organised!".to_string());
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Burrow
+{
+    hallway: [char; HALLWAY_LEN],
+    // Each room is stored from the hallway down, only the first `depth` slots are used
+    rooms: [[char; MAX_DEPTH]; NUM_ROOMS],
+    depth: usize
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location
+{
+    Hallway(usize),
+    Room(usize, usize) // (room, depth)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move
+{
+    amphipod: char,
+    from: Location,
+    to: Location,
+    energy: usize
+}
+
+fn get_energy_per_step(amphipod: char) -> usize
+{
+    return match amphipod
+    {
+        'A' => 1,
+        'B' => 10,
+        'C' => 100,
+        'D' => 1000,
+         _  => panic!("Unknown amphipod!")
+    };
+}
+
+fn is_amphipod(c: char)            -> bool  { ('A'..=get_room_owner(NUM_ROOMS - 1)).contains(&c) }
+fn get_target_room(amphipod: char) -> usize { (amphipod as u8 - b'A') as usize }
+fn get_room_owner(room: usize)     -> char  { (b'A' + room as u8) as char }
+fn get_room_entrance(room: usize)  -> usize { 2 + 2 * room }
+fn is_room_entrance(x: usize)      -> bool  { (2..=8).contains(&x) && x.is_multiple_of(2) }
+
+impl Burrow
+{
+    pub fn new_from_file(path: &str) -> Result<Self, String>
+    {
+        let input = parse_input_at(path).map_err(|why| why.to_string())?;
+        return Self::parse(&input);
+    }
+
+    // Only amphipods with a room of their own, A to D, are accepted, in rooms
+    // 2 deep (folded) or 4 deep (unfolded)
+    pub fn parse(input: &str) -> Result<Self, String>
+    {
+        let mut burrow = Self
+        {
+            hallway: [EMPTY; HALLWAY_LEN],
+            rooms:   [[EMPTY; MAX_DEPTH]; NUM_ROOMS],
+            depth:   0
+        };
+
+        let mut lines = input.lines().skip(1); // Skip the top wall
+
+        let hallway = lines.next().unwrap_or_default();
+        for (x, c) in hallway.chars().skip(1).take(HALLWAY_LEN).enumerate()
+        {
+            if c != EMPTY && !is_amphipod(c) { return Err(format!("Unknown amphipod '{}' in the hallway", c)); }
+            burrow.hallway[x] = c;
+        }
+
+        for line in lines
+        {
+            let row: Vec<char> = line.chars().collect();
+            let is_room_row = row.len() > 3 && (row[3] == EMPTY || row[3].is_ascii_uppercase());
+            if !is_room_row || burrow.depth == MAX_DEPTH { break; }
+
+            for room in 0..NUM_ROOMS
+            {
+                let c = row.get(3 + 2 * room)
+                           .copied()
+                           .unwrap_or(EMPTY);
+                if c != EMPTY && !is_amphipod(c) { return Err(format!("Unknown amphipod '{}' in room {}", c, room)); }
+                burrow.rooms[room][burrow.depth] = c;
+            }
+            burrow.depth += 1;
+        }
+
+        if burrow.depth != 2 && burrow.depth != MAX_DEPTH
+        {
+            return Err(format!("Rooms must be 2 or {} deep, not {}", MAX_DEPTH, burrow.depth));
+        }
+        return Ok(burrow);
+    }
+
+    // Second puzzle: reveal the two rows hidden in the middle of the diagram
+    pub fn unfold(&self) -> Result<Self, String>
+    {
+        if self.depth != 2 { return Err(format!("Only rooms 2 deep can be unfolded, not {}", self.depth)); }
+
+        let mut unfolded = *self;
+        unfolded.depth   = 4;
+        for (room, cells) in unfolded.rooms.iter_mut().enumerate()
+        {
+            *cells = [self.rooms[room][0],
+                      FOLDED_ROWS[0][room],
+                      FOLDED_ROWS[1][room],
+                      self.rooms[room][1]];
+        }
+        return Ok(unfolded);
+    }
+
+    pub fn is_organised(&self) -> bool
+    {
+        if self.hallway.iter().any(|&c| c != EMPTY) { return false; }
+
+        for room in 0..NUM_ROOMS
+        {
+            let owner = get_room_owner(room);
+            if self.rooms[room][..self.depth].iter().any(|&c| c != owner) { return false; }
+        }
+        return true;
+    }
+
+    // A room can be entered only if it holds nothing but its own amphipods.
+    // Those are also the rooms nobody needs to leave.
+    fn is_room_open(&self, room: usize) -> bool
+    {
+        let owner = get_room_owner(room);
+        return self.rooms[room][..self.depth].iter()
+                                             .all(|&c| c == EMPTY || c == owner);
+    }
+
+    // Checks every hallway position between `from` (excluded) and `to` (included)
+    fn is_hallway_clear(&self, from: usize, to: usize) -> bool
+    {
+        let range = if from < to { from+1 ..= to } else { to ..= from-1 };
+        return self.hallway[range].iter().all(|&c| c == EMPTY);
+    }
+
+    pub fn get_legal_moves(&self) -> Vec<Move>
+    {
+        let mut moves = Vec::new();
+
+        // Once in the hallway, an amphipod can only move into its own room
+        for x in 0..HALLWAY_LEN
+        {
+            let amphipod = self.hallway[x];
+            if amphipod == EMPTY { continue; }
+
+            if let Some(mv) = self.get_move_home(amphipod, Location::Hallway(x), x, 0)
+            {
+                moves.push(mv);
+            }
+        }
+
+        for room in 0..NUM_ROOMS
+        {
+            if self.is_room_open(room) { continue; }
+
+            let depth = match self.rooms[room][..self.depth].iter().position(|&c| c != EMPTY)
+            {
+                Some(depth) => depth,
+                None        => continue
+            };
+            let amphipod   = self.rooms[room][depth];
+            let from       = Location::Room(room, depth);
+            let entrance   = get_room_entrance(room);
+            let exit_steps = depth + 1;
+
+            // Going straight home is never worse than stopping in the hallway first
+            if let Some(mv) = self.get_move_home(amphipod, from, entrance, exit_steps)
+            {
+                moves.push(mv);
+                continue;
+            }
+
+            for x in 0..HALLWAY_LEN
+            {
+                // Amphipods never stop right outside a room
+                if is_room_entrance(x) || !self.is_hallway_clear(entrance, x) { continue; }
+
+                let steps = exit_steps + entrance.abs_diff(x);
+                moves.push( Move{ amphipod, from, to: Location::Hallway(x), energy: steps * get_energy_per_step(amphipod) } );
+            }
+        }
+
+        return moves;
+    }
+
+    // `x` is the hallway position the amphipod starts walking from, and
+    // `steps_so_far` the steps it took to get there
+    fn get_move_home(&self, amphipod: char, from: Location, x: usize, steps_so_far: usize) -> Option<Move>
+    {
+        let room     = get_target_room(amphipod);
+        let entrance = get_room_entrance(room);
+
+        if !self.is_room_open(room) || !self.is_hallway_clear(x, entrance) { return None; }
+
+        // Always go as deep as possible, so nobody gets blocked in
+        let depth = self.rooms[room][..self.depth].iter().rposition(|&c| c == EMPTY)?;
+        let steps = steps_so_far + x.abs_diff(entrance) + depth + 1;
+
+        return Some( Move{ amphipod, from, to: Location::Room(room, depth), energy: steps * get_energy_per_step(amphipod) } );
+    }
+
+    pub fn apply(&self, mv: &Move) -> Self
+    {
+        let mut next = *self;
+        *next.get_cell_mut(mv.from) = EMPTY;
+        *next.get_cell_mut(mv.to)   = mv.amphipod;
+        return next;
+    }
+
+    fn get_cell_mut(&mut self, location: Location) -> &mut char
+    {
+        return match location
+        {
+            Location::Hallway(x)        => &mut self.hallway[x],
+            Location::Room(room, depth) => &mut self.rooms[room][depth]
+        };
+    }
+
+    pub fn render(&self) -> String
+    {
+        let mut result = String::new();
+        result += "#############\n";
+        result += &format!("#{}#\n", self.hallway.iter().collect::<String>());
+
+        for depth in 0..self.depth
+        {
+            let cells: Vec<String> = self.rooms.iter()
+                                               .map(|room| room[depth].to_string())
+                                               .collect();
+            let (left, right) = if depth == 0 { ("##", "##") } else { ("  ", "") };
+            result += &format!("{}#{}#{}\n", left, cells.join("#"), right);
+        }
+        result += "  #########\n";
+
+        return result;
+    }
+
+    pub fn print(&self)
+    {
+        print!("{}", self.render());
+    }
+}
+
+// Dijkstra over burrow states
+// Returns the minimal total energy and the sequence of moves that achieves it
+fn organise(start: &Burrow) -> Option<(usize, Vec<Move>)>
+{
+    let mut energy_so_far: HashMap<Burrow, usize>         = HashMap::new();
+    let mut prev_state:    HashMap<Burrow, (Burrow, Move)> = HashMap::new();
+    let mut candidates = BinaryHeap::new();
+
+    energy_so_far.insert(*start, 0);
+    candidates.push( Reverse((0, *start)) );
+
+    while let Some( Reverse((energy, burrow)) ) = candidates.pop()
+    {
+        if burrow.is_organised()
+        {
+            return Some( (energy, rebuild_moves(&prev_state, start, burrow)) );
+        }
+
+        // Outdated entry, a cheaper way here was already expanded
+        if energy > *energy_so_far.get(&burrow).unwrap_or(&usize::MAX) { continue; }
+
+        for mv in burrow.get_legal_moves()
+        {
+            let next       = burrow.apply(&mv);
+            let new_energy = energy + mv.energy;
+            if new_energy >= *energy_so_far.get(&next).unwrap_or(&usize::MAX) { continue; }
+
+            energy_so_far.insert(next, new_energy);
+            prev_state.insert(next, (burrow, mv));
+            candidates.push( Reverse((new_energy, next)) );
+        }
+    }
+
+    return None;
+}
+
+fn rebuild_moves(prev_state: &HashMap<Burrow, (Burrow, Move)>, start: &Burrow, end: Burrow) -> Vec<Move>
+{
+    let mut moves   = Vec::new();
+    let mut current = end;
+    while current != *start
+    {
+        let (prev, mv) = prev_state[&current];
+        moves.push(mv);
+        current = prev;
+    }
+    moves.reverse();
+    return moves;
+}
+
+// None if the burrow can't be organised
+fn find_minimal_energy(burrow: &Burrow) -> Option<usize>
+{
+    return organise(burrow).map(|(energy, _)| energy);
+}
+
+pub fn print_optimal_moves(burrow: &Burrow)
+{
+    let (energy, moves) = match organise(burrow)
+    {
+        Some(solution) => solution,
+        None           => { println!("The burrow can't be organised!"); return; }
+    };
+
+    let mut current = *burrow;
+    current.print();
+    for mv in moves
+    {
+        println!("{} moves from {:?} to {:?} ({} energy)", mv.amphipod, mv.from, mv.to, mv.energy);
+        current = current.apply(&mv);
+        current.print();
+    }
+    println!("Total energy: {}", energy);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_first_puzzle()
+    {
+        assert_eq!(day_23_1("src/inputs/day_23_example.txt"), Ok(12521));
+    }
+
+    #[test]
+    fn test_second_puzzle()
+    {
+        assert_eq!(day_23_2("src/inputs/day_23_example.txt"), Ok(44169));
+    }
+
+    #[test]
+    fn test_parse_burrow()
+    {
+        let burrow = Burrow::new_from_file("src/inputs/day_23_example.txt").unwrap();
+
+        assert_eq!(burrow.depth, 2);
+        assert_eq!(burrow.hallway, [EMPTY; HALLWAY_LEN]);
+        assert_eq!(burrow.rooms[0][..2], ['B', 'A']);
+        assert_eq!(burrow.rooms[3][..2], ['D', 'A']);
+        assert!(!burrow.is_organised());
+    }
+
+    #[test]
+    fn test_unknown_amphipods()
+    {
+        let input = parse_input_at("src/inputs/day_23_example.txt").unwrap();
+
+        let in_room = input.replacen('B', "E", 1);
+        assert_eq!(Burrow::parse(&in_room).err(), Some("Unknown amphipod 'E' in room 0".to_string()));
+
+        let in_hallway = input.replacen("#...........#", "#.....Z.....#", 1);
+        assert_eq!(Burrow::parse(&in_hallway).err(), Some("Unknown amphipod 'Z' in the hallway".to_string()));
+    }
+
+    #[test]
+    fn test_render_round_trip()
+    {
+        let input  = parse_input_at("src/inputs/day_23_example.txt").unwrap();
+        let burrow = Burrow::parse(&input).unwrap();
+
+        assert_eq!(burrow.render().trim_end(), input.trim_end());
+        assert!(Burrow::parse(&burrow.render()) == Ok(burrow));
+    }
+
+    #[test]
+    fn test_unfold()
+    {
+        let burrow = Burrow::new_from_file("src/inputs/day_23_example.txt").unwrap().unfold().unwrap();
+
+        let expected = "#############\n\
+                        #...........#\n\
+                        ###B#C#B#D###\n\
+                        \x20 #D#C#B#A#\n\
+                        \x20 #D#B#A#C#\n\
+                        \x20 #A#D#C#A#\n\
+                        \x20 #########\n";
+        assert_eq!(burrow.depth, 4);
+        assert_eq!(burrow.render(), expected);
+
+        // Already unfolded
+        assert!(Burrow::parse(expected).unwrap().unfold().is_err());
+    }
+
+    #[test]
+    fn test_invalid_depths()
+    {
+        assert_eq!(Burrow::parse("").err(), Some("Rooms must be 2 or 4 deep, not 0".to_string()));
+
+        let input = parse_input_at("src/inputs/day_23_example.txt").unwrap();
+        let lines: Vec<&str> = input.lines().collect();
+
+        let shallow = [&lines[..3], &lines[4..]].concat().join("\n");
+        assert_eq!(Burrow::parse(&shallow).err(), Some("Rooms must be 2 or 4 deep, not 1".to_string()));
+
+        let deeper = [&lines[..4], &lines[3..]].concat().join("\n");
+        assert_eq!(Burrow::parse(&deeper).err(), Some("Rooms must be 2 or 4 deep, not 3".to_string()));
+    }
+
+    #[test]
+    fn test_initial_moves()
+    {
+        let burrow = Burrow::new_from_file("src/inputs/day_23_example.txt").unwrap();
+        let moves  = burrow.get_legal_moves();
+
+        // Every top amphipod can stop at any of the 7 hallway positions
+        assert_eq!(moves.len(), 4 * 7);
+        assert!(moves.iter().all(|mv| matches!(mv.to, Location::Hallway(_))));
+    }
+
+    #[test]
+    fn test_no_moves_through_blocked_hallway()
+    {
+        let mut burrow = Burrow::new_from_file("src/inputs/day_23_example.txt").unwrap();
+        burrow.hallway[3] = 'A';
+        burrow.hallway[5] = 'D';
+
+        // B in room 1 is boxed in between positions 3 and 5
+        let moves = burrow.get_legal_moves();
+        assert!(moves.iter().all(|mv| mv.from != Location::Room(1, 0)));
+    }
+
+    #[test]
+    fn test_move_home_goes_deepest()
+    {
+        let mut burrow = Burrow::new_from_file("src/inputs/day_23_example.txt").unwrap();
+        burrow.rooms[0]   = [EMPTY; MAX_DEPTH];
+        burrow.hallway[1] = 'A';
+
+        let mv = burrow.get_move_home('A', Location::Hallway(1), 1, 0).unwrap();
+        assert_eq!(mv.to, Location::Room(0, 1));
+        assert_eq!(mv.energy, 3);
+    }
+
+    #[test]
+    fn test_deadlock()
+    {
+        // D needs to get past A and A past D, and neither can step back
+        let burrow = Burrow::parse("#############\n\
+                                    #...D.A.....#\n\
+                                    ###.#B#C#.###\n\
+                                    \x20 #A#B#C#D#\n\
+                                    \x20 #########\n").unwrap();
+
+        assert!(burrow.get_legal_moves().is_empty());
+        assert_eq!(find_minimal_energy(&burrow), None);
+    }
+
+    #[test]
+    fn test_optimal_moves_replay()
+    {
+        let burrow = Burrow::new_from_file("src/inputs/day_23_example.txt").unwrap();
+        let (energy, moves) = organise(&burrow).unwrap();
+
+        let mut current = burrow;
+        for mv in &moves
+        {
+            assert!(current.get_legal_moves().contains(mv));
+            current = current.apply(mv);
+        }
+        assert!(current.is_organised());
+        assert_eq!(moves.iter().map(|mv| mv.energy).sum::<usize>(), energy);
+    }
+}
@@ -0,0 +1,462 @@
+use std::collections::HashSet;
+
+use crate::input_parser::parse_input_at;
+
+const MODEL_NUMBER_LEN: usize = 14;
+
+pub fn day_24_1(input_path: &str) -> Result<u64, String>
+{
+    let program = parse_program_from_file(input_path)?;
+    return find_model_number(&program, &[9,8,7,6,5,4,3,2,1]).ok_or("No valid model number".to_string());
+}
+
+pub fn day_24_2(input_path: &str) -> Result<u64, String>
+{
+    let program = parse_program_from_file(input_path)?;
+    return find_model_number(&program, &[1,2,3,4,5,6,7,8,9]).ok_or("No valid model number".to_string());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register
+{
+    W = 0,
+    X,
+    Y,
+    Z
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand
+{
+    Register(Register),
+    Value(i64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction
+{
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Alu
+{
+    registers: [i64; 4]
+}
+
+fn parse_program_from_file(path: &str) -> Result<Vec<Instruction>, String>
+{
+    let input = parse_input_at(path).map_err(|why| why.to_string())?;
+    return parse_program(&input);
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, String>
+{
+    let mut program = Vec::new();
+    for (i, line) in input.lines().enumerate()
+    {
+        if line.trim().is_empty() { continue; }
+        program.push( parse_instruction(line).map_err(|why| format!("Line {}: {}", i+1, why))? );
+    }
+    return Ok(program);
+}
+
+fn parse_instruction(entry: &str) -> Result<Instruction, String>
+{
+    let mut words = entry.split_whitespace();
+
+    let mut next_word = || words.next().ok_or(format!("Missing operand in '{}'", entry));
+
+    let op_code = next_word()?;
+    let a       = parse_register(next_word()?)?;
+
+    if op_code == "inp" { return Ok(Instruction::Inp(a)); }
+
+    let b = parse_operand(next_word()?)?;
+    return match op_code
+    {
+        "add" => Ok(Instruction::Add(a, b)),
+        "mul" => Ok(Instruction::Mul(a, b)),
+        "div" => Ok(Instruction::Div(a, b)),
+        "mod" => Ok(Instruction::Mod(a, b)),
+        "eql" => Ok(Instruction::Eql(a, b)),
+         _    => Err(format!("Unknown instruction '{}'", op_code))
+    };
+}
+
+fn parse_register(entry: &str) -> Result<Register, String>
+{
+    return match entry
+    {
+        "w" => Ok(Register::W),
+        "x" => Ok(Register::X),
+        "y" => Ok(Register::Y),
+        "z" => Ok(Register::Z),
+         _  => Err(format!("Unknown register '{}'", entry))
+    };
+}
+
+fn parse_operand(entry: &str) -> Result<Operand, String>
+{
+    return match entry.parse::<i64>()
+    {
+        Ok(value) => Ok(Operand::Value(value)),
+        Err(_)    => Ok(Operand::Register( parse_register(entry)? ))
+    };
+}
+
+impl Alu
+{
+    pub fn new() -> Self { Self::default() }
+
+    pub fn get(&self, register: Register) -> i64 { self.registers[register as usize] }
+
+    fn get_operand(&self, operand: Operand) -> i64
+    {
+        return match operand
+        {
+            Operand::Register(r) => self.get(r),
+            Operand::Value(v)    => v
+        };
+    }
+
+    // Returns None if the program crashes: division by zero, invalid modulo,
+    // overflow or running out of input
+    pub fn execute(&mut self, program: &[Instruction], input: &[i64]) -> Option<()>
+    {
+        let mut input = input.iter();
+        for instruction in program
+        {
+            let (a, result) = match *instruction
+            {
+                Instruction::Inp(a)    => (a, *input.next()?),
+                Instruction::Add(a, b) => (a, self.get(a).checked_add( self.get_operand(b) )?),
+                Instruction::Mul(a, b) => (a, self.get(a).checked_mul( self.get_operand(b) )?),
+                Instruction::Div(a, b) => (a, self.get(a).checked_div( self.get_operand(b) )?),
+                Instruction::Mod(a, b) =>
+                {
+                    let (val_a, val_b) = (self.get(a), self.get_operand(b));
+                    if val_a < 0 || val_b <= 0 { return None; }
+                    (a, val_a % val_b)
+                },
+                Instruction::Eql(a, b) => (a, (self.get(a) == self.get_operand(b)) as i64)
+            };
+            self.registers[a as usize] = result;
+        }
+        return Some(());
+    }
+}
+
+fn get_digits(number: u64) -> Vec<i64>
+{
+    return number.to_string()
+                 .chars()
+                 .map(|c| c.to_digit(10).unwrap_or_default() as i64)
+                 .collect();
+}
+
+// A model number is valid if it has 14 digits, none of them zero, and MONAD
+// finishes with 0 in z
+pub fn is_model_number_valid(program: &[Instruction], number: u64) -> bool
+{
+    let digits = get_digits(number);
+    if digits.len() != MODEL_NUMBER_LEN || digits.contains(&0) { return false; }
+
+    let mut alu = Alu::new();
+    return alu.execute(program, &digits).is_some() && alu.get(Register::Z) == 0;
+}
+
+// Each `inp` starts a new block that processes one digit
+fn split_in_blocks(program: &[Instruction]) -> Vec<Vec<Instruction>>
+{
+    let mut blocks: Vec<Vec<Instruction>> = Vec::new();
+    for instruction in program
+    {
+        if matches!(instruction, Instruction::Inp(_)) || blocks.is_empty()
+        {
+            blocks.push(Vec::new());
+        }
+        blocks.last_mut().unwrap().push(*instruction);
+    }
+    return blocks;
+}
+
+// Every block of the puzzle's MONAD is this, only the `div z` (line 4), `add x`
+// (line 5) and `add y` (line 15) values change
+const MONAD_BLOCK: &str = "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z 1\nadd x 0\neql x w\neql x 0\n\
+                           mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\n\
+                           mul y 0\nadd y w\nadd y 0\nmul y x\nadd z y\n";
+
+// A positive divisor and a non negative `add y` keep z from shrinking anywhere
+// else, which is what the z limits rely on
+fn is_monad_block(block: &[Instruction]) -> bool
+{
+    let template = parse_program(MONAD_BLOCK).unwrap(); // Known to be valid
+    if block.len() != template.len() { return false; }
+
+    return block.iter().zip(template.iter()).enumerate().all(|(i, (instruction, expected))|
+    {
+        match (i, *instruction)
+        {
+            (4,  Instruction::Div(Register::Z, Operand::Value(v))) => v >= 1,
+            (5,  Instruction::Add(Register::X, Operand::Value(_))) => true,
+            (15, Instruction::Add(Register::Y, Operand::Value(v))) => v >= 0,
+            (4 | 5 | 15, _)                                        => false,
+             _                                                     => instruction == expected
+        }
+    });
+}
+
+// MONAD can only shrink z through its `div z N` instructions, so once z is at
+// least the product of the remaining divisors it can't get back to 0
+fn get_z_limits(blocks: &[Vec<Instruction>]) -> Vec<i64>
+{
+    let mut limits: Vec<i64> = vec![1; blocks.len() + 1];
+    for i in (0..blocks.len()).rev()
+    {
+        let mut divisor = 1;
+        for instruction in &blocks[i]
+        {
+            if let Instruction::Div(Register::Z, Operand::Value(v)) = instruction
+            {
+                divisor *= v.abs().max(1);
+            }
+        }
+        limits[i] = limits[i+1].saturating_mul(divisor);
+    }
+    return limits;
+}
+
+// Depth-first search over the digits, trying them in the given order so the
+// first hit is the answer.
+// When every block has the MONAD shape, w, x and y are reset by each block so
+// only z is remembered, and hopeless z values are cut early. Any other program
+// is searched remembering the whole ALU, which is exact but much slower.
+pub fn find_model_number(program: &[Instruction], digit_order: &[i64]) -> Option<u64>
+{
+    let blocks = split_in_blocks(program);
+    if blocks.len() != MODEL_NUMBER_LEN { return None; }
+
+    let z_limits      = if blocks.iter().all(|block| is_monad_block(block)) { Some(get_z_limits(&blocks)) } else { None };
+    let mut dead_ends = HashSet::new();
+
+    return search_from(&blocks, z_limits.as_deref(), digit_order, 0, Alu::new(), 0, &mut dead_ends);
+}
+
+fn search_from(blocks: &[Vec<Instruction>],
+               z_limits: Option<&[i64]>,
+               digit_order: &[i64],
+               block_idx: usize,
+               mut alu: Alu,
+               prefix: u64,
+               dead_ends: &mut HashSet<(usize, Alu)>)
+-> Option<u64>
+{
+    let z = alu.get(Register::Z);
+    if block_idx == blocks.len()
+    {
+        return if z == 0 { Some(prefix) } else { None };
+    }
+
+    if let Some(z_limits) = z_limits
+    {
+        if z >= z_limits[block_idx] { return None; }
+        alu = Alu::new();
+        alu.registers[Register::Z as usize] = z;
+    }
+    if dead_ends.contains(&(block_idx, alu)) { return None; }
+
+    for &digit in digit_order
+    {
+        let mut next = alu;
+        if next.execute(&blocks[block_idx], &[digit]).is_none() { continue; }
+
+        let result = search_from(blocks,
+                                 z_limits,
+                                 digit_order,
+                                 block_idx + 1,
+                                 next,
+                                 prefix * 10 + digit as u64,
+                                 dead_ends);
+        if result.is_some() { return result; }
+    }
+
+    dead_ends.insert((block_idx, alu));
+    return None;
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // Same shape as the puzzle input: (div z, add x, add y) per digit
+    const MONAD_PARAMS: [(i64, i64, i64); MODEL_NUMBER_LEN] = [(1, 12, 7),  (1, 11, 15), (26, -8, 2),
+                                                               (1, 13, 4),  (26, -2, 11), (26, -6, 9),
+                                                               (1, 14, 1),  (1, 10, 6),  (26, -4, 3),
+                                                               (1, 15, 8),  (26, -13, 5), (26, -7, 12),
+                                                               (1, 11, 10), (26, -9, 14)];
+
+    fn build_monad() -> Vec<Instruction>
+    {
+        let mut source = String::new();
+        for (div, add_x, add_y) in MONAD_PARAMS
+        {
+            source += &format!("inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\n\
+                                mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\n\
+                                mul y 0\nadd y w\nadd y {}\nmul y x\nadd z y\n",
+                               div, add_x, add_y);
+        }
+        return parse_program(&source).unwrap();
+    }
+
+    #[test]
+    fn test_parse_instruction()
+    {
+        assert_eq!(parse_instruction("inp w"),    Ok(Instruction::Inp(Register::W)));
+        assert_eq!(parse_instruction("add z y"),  Ok(Instruction::Add(Register::Z, Operand::Register(Register::Y))));
+        assert_eq!(parse_instruction("mul x -1"), Ok(Instruction::Mul(Register::X, Operand::Value(-1))));
+        assert_eq!(parse_instruction("eql x 0"),  Ok(Instruction::Eql(Register::X, Operand::Value(0))));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(parse_instruction("sub x 1"), Err("Unknown instruction 'sub'".to_string()));
+        assert_eq!(parse_instruction("add q 1"), Err("Unknown register 'q'".to_string()));
+        assert_eq!(parse_instruction("mul x"),   Err("Missing operand in 'mul x'".to_string()));
+        assert_eq!(parse_program("inp w\nadd w v"), Err("Line 2: Unknown register 'v'".to_string()));
+
+        assert!(day_24_1("src/inputs/day_24_missing.txt").is_err());
+    }
+
+    #[test]
+    fn test_no_model_number()
+    {
+        let program = parse_program(&"inp w\nadd z 1\n".repeat(MODEL_NUMBER_LEN)).unwrap();
+        assert_eq!(find_model_number(&program, &[9,8,7,6,5,4,3,2,1]), None);
+    }
+
+    #[test]
+    fn test_negate()
+    {
+        let program = parse_program("inp x\nmul x -1").unwrap();
+        let mut alu = Alu::new();
+
+        assert!(alu.execute(&program, &[7]).is_some());
+        assert_eq!(alu.get(Register::X), -7);
+    }
+
+    #[test]
+    fn test_three_times_larger()
+    {
+        let program = parse_program("inp z\ninp x\nmul z 3\neql z x").unwrap();
+
+        let mut alu = Alu::new();
+        alu.execute(&program, &[2, 6]);
+        assert_eq!(alu.get(Register::Z), 1);
+
+        let mut alu = Alu::new();
+        alu.execute(&program, &[2, 5]);
+        assert_eq!(alu.get(Register::Z), 0);
+    }
+
+    #[test]
+    fn test_binary_conversion()
+    {
+        let program = parse_program("inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\n\
+                                     add x w\nmod x 2\ndiv w 2\nmod w 2").unwrap();
+        let mut alu = Alu::new();
+        alu.execute(&program, &[13]);
+
+        assert_eq!(alu.registers, [1, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_crashes()
+    {
+        let mut alu = Alu::new();
+        assert!(alu.execute(&parse_program("div x 0").unwrap(), &[]).is_none());
+
+        let mut alu = Alu::new();
+        assert!(alu.execute(&parse_program("add x -1\nmod x 2").unwrap(), &[]).is_none());
+
+        let mut alu = Alu::new();
+        assert!(alu.execute(&parse_program("inp x\ninp y").unwrap(), &[1]).is_none());
+
+        let mut alu = Alu::new();
+        assert!(alu.execute(&parse_program("inp x\nmul x 9223372036854775807\nmul x 9").unwrap(), &[1]).is_none());
+
+        let mut alu = Alu::new();
+        assert!(alu.execute(&parse_program("add x 9223372036854775807\nadd x 1").unwrap(), &[]).is_none());
+    }
+
+    #[test]
+    fn test_division_truncates_towards_zero()
+    {
+        let mut alu = Alu::new();
+        alu.execute(&parse_program("add x -7\ndiv x 2").unwrap(), &[]);
+        assert_eq!(alu.get(Register::X), -3);
+    }
+
+    #[test]
+    fn test_first_puzzle()
+    {
+        let program = build_monad();
+        let largest = find_model_number(&program, &[9,8,7,6,5,4,3,2,1]);
+
+        assert_eq!(largest, Some(82979997994389));
+        assert!(is_model_number_valid(&program, 82979997994389));
+        assert!(!is_model_number_valid(&program, 82979997994399));
+    }
+
+    #[test]
+    fn test_second_puzzle()
+    {
+        let program  = build_monad();
+        let smallest = find_model_number(&program, &[1,2,3,4,5,6,7,8,9]);
+
+        assert_eq!(smallest, Some(11813271361112));
+        assert!(is_model_number_valid(&program, 11813271361112));
+    }
+
+    #[test]
+    fn test_monad_shape()
+    {
+        let blocks = split_in_blocks(&build_monad());
+        assert!(blocks.iter().all(|block| is_monad_block(block)));
+
+        let mut block = blocks[0].clone();
+        block[12] = Instruction::Add(Register::Z, Operand::Register(Register::Y));
+        assert!(!is_monad_block(&block));
+
+        // A negative `add y` could shrink z without dividing it
+        let mut block = blocks[0].clone();
+        block[15] = Instruction::Add(Register::Y, Operand::Value(-5));
+        assert!(!is_monad_block(&block));
+    }
+
+    #[test]
+    fn test_other_programs_are_not_pruned()
+    {
+        // z only gets back to 0 if the digits add up to 70, no `div z` at all
+        let program = parse_program(&"inp w\nadd z w\nadd z -5\n".repeat(MODEL_NUMBER_LEN)).unwrap();
+
+        assert_eq!(find_model_number(&program, &[9,8,7,6,5,4,3,2,1]), Some(99999991111111));
+        assert_eq!(find_model_number(&program, &[1,2,3,4,5,6,7,8,9]), Some(11111119999999));
+    }
+
+    #[test]
+    fn test_invalid_model_number_shapes()
+    {
+        let program = build_monad();
+
+        assert!(!is_model_number_valid(&program, 1181327136111));   // Too short
+        assert!(!is_model_number_valid(&program, 11813271361102));  // Has a 0
+    }
+}
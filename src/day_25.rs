@@ -0,0 +1,217 @@
+use crate::input_parser::parse_input_at;
+
+const EAST:  char = '>';
+const SOUTH: char = 'v';
+const EMPTY: char = '.';
+
+pub fn day_25_1(input_path: &str) -> Result<usize, String>
+{
+    let mut sea_floor = SeaFloor::new_from_file(input_path)?;
+    return Ok(sea_floor.simulate_until_still(false));
+}
+
+// Same as the first puzzle, printing the sea floor after every step
+pub fn day_25_print_steps(input_path: &str) -> Result<usize, String>
+{
+    let mut sea_floor = SeaFloor::new_from_file(input_path)?;
+    return Ok(sea_floor.simulate_until_still(true));
+}
+
+pub struct SeaFloor
+{
+    // NOTE: ROW MAJOR!
+    cells: Vec<char>,
+    num_rows: usize,
+    num_cols: usize
+}
+
+impl SeaFloor
+{
+    pub fn new_from_file(path: &str) -> Result<Self, String>
+    {
+        let input = parse_input_at(path).map_err(|why| why.to_string())?;
+        return Self::parse(&input);
+    }
+
+    // Every row must be as wide as the first one, with only '>', 'v' and '.'
+    pub fn parse(input: &str) -> Result<Self, String>
+    {
+        let mut sea_floor = Self{ cells: Vec::new(), num_rows: 0, num_cols: 0 };
+        for (i, line) in input.lines().enumerate()
+        {
+            if line.is_empty() { continue; }
+
+            if let Some(c) = line.chars().find(|&c| c != EAST && c != SOUTH && c != EMPTY)
+            {
+                return Err(format!("Line {}: unknown cell '{}'", i+1, c));
+            }
+            if sea_floor.num_rows > 0 && line.len() != sea_floor.num_cols
+            {
+                return Err(format!("Line {}: expected {} cells, found {}", i+1, sea_floor.num_cols, line.len()));
+            }
+
+            sea_floor.cells.extend( line.chars() );
+            sea_floor.num_cols = line.len();
+            sea_floor.num_rows += 1;
+        }
+
+        if sea_floor.cells.is_empty() { return Err("Empty sea floor".to_string()); }
+        return Ok(sea_floor);
+    }
+
+    // Returns the number of the first step on which no sea cucumber moves
+    pub fn simulate_until_still(&mut self, render: bool) -> usize
+    {
+        if render
+        {
+            println!("Initial state:");
+            self.print();
+        }
+
+        let mut step_count = 1;
+        while self.step() > 0
+        {
+            if render
+            {
+                println!("After {} steps:", step_count);
+                self.print();
+            }
+            step_count += 1;
+        }
+        return step_count;
+    }
+
+    // Returns how many sea cucumbers moved
+    pub fn step(&mut self) -> usize
+    {
+        return self.move_herd(EAST) + self.move_herd(SOUTH);
+    }
+
+    // Every sea cucumber in the herd looks at its destination before any of
+    // them moves, so the whole herd moves simultaneously
+    fn move_herd(&mut self, herd: char) -> usize
+    {
+        let mut moves = Vec::new();
+        for i in 0..self.cells.len()
+        {
+            if self.cells[i] != herd { continue; }
+
+            let destination = self.get_destination(i, herd);
+            if self.cells[destination] == EMPTY
+            {
+                moves.push((i, destination));
+            }
+        }
+
+        for (from, to) in &moves
+        {
+            self.cells[*from] = EMPTY;
+            self.cells[*to]   = herd;
+        }
+        return moves.len();
+    }
+
+    // Sea cucumbers leaving an edge reappear on the opposite one
+    fn get_destination(&self, i: usize, herd: char) -> usize
+    {
+        let y = i / self.num_cols;
+        let x = i - y * self.num_cols;
+
+        return match herd
+        {
+            EAST  => y * self.num_cols + (x + 1) % self.num_cols,
+            SOUTH => ((y + 1) % self.num_rows) * self.num_cols + x,
+              _   => panic!("Unknown herd!")
+        };
+    }
+
+    pub fn render(&self) -> String
+    {
+        let mut result = String::with_capacity(self.cells.len() + self.num_rows);
+        for row in self.cells.chunks(self.num_cols.max(1))
+        {
+            result.extend(row);
+            result.push('\n');
+        }
+        return result;
+    }
+
+    pub fn print(&self)
+    {
+        println!("{}", self.render());
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_first_puzzle()
+    {
+        assert_eq!(day_25_1("src/inputs/day_25_example.txt"), Ok(58));
+        assert_eq!(day_25_print_steps("src/inputs/day_25_example.txt"), Ok(58));
+    }
+
+    #[test]
+    fn test_parse_sea_floor()
+    {
+        let sea_floor = SeaFloor::new_from_file("src/inputs/day_25_example.txt").unwrap();
+
+        assert_eq!(sea_floor.num_rows, 9);
+        assert_eq!(sea_floor.num_cols, 10);
+        assert_eq!(sea_floor.cells.len(), 90);
+    }
+
+    #[test]
+    fn test_invalid_sea_floors()
+    {
+        assert_eq!(SeaFloor::parse("v..\n..\n.>").err(), Some("Line 2: expected 3 cells, found 2".to_string()));
+        assert_eq!(SeaFloor::parse("v..\n.x.").err(),     Some("Line 2: unknown cell 'x'".to_string()));
+        assert_eq!(SeaFloor::parse("").err(),              Some("Empty sea floor".to_string()));
+
+        assert!(day_25_1("src/inputs/day_25_missing.txt").is_err());
+    }
+
+    #[test]
+    fn test_east_herd_moves_simultaneously()
+    {
+        let mut sea_floor = SeaFloor::parse("...>>>>>...").unwrap();
+
+        sea_floor.step();
+        assert_eq!(sea_floor.render(), "...>>>>.>..\n");
+        sea_floor.step();
+        assert_eq!(sea_floor.render(), "...>>>.>.>.\n");
+    }
+
+    #[test]
+    fn test_east_moves_before_south()
+    {
+        let mut sea_floor = SeaFloor::parse("..........\n.>v....v..\n.......>..\n..........").unwrap();
+        sea_floor.step();
+
+        assert_eq!(sea_floor.render(), "..........\n.>........\n..v....v>.\n..........\n");
+    }
+
+    #[test]
+    fn test_wrap_around()
+    {
+        let mut sea_floor = SeaFloor::parse("...>...\n.......\n......>\nv.....>\n......>\n.......\n..vvv..").unwrap();
+        sea_floor.step();
+
+        let expected = "..vv>..\n.......\n>......\nv.....>\n>......\n.......\n....v..\n";
+        assert_eq!(sea_floor.render(), expected);
+    }
+
+    #[test]
+    fn test_example_steps()
+    {
+        let mut sea_floor = SeaFloor::new_from_file("src/inputs/day_25_example.txt").unwrap();
+        for _ in 0..57
+        {
+            assert!(sea_floor.step() > 0);
+        }
+        assert_eq!(sea_floor.step(), 0);
+    }
+}
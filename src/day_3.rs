@@ -1,62 +1,98 @@
 use crate::input_parser::*;
 
-pub fn day_3_1() -> i32
+pub fn day_3_1() -> Result<u128, String>
 {
     let input = parse_input_at("src/inputs/day_3_input.txt")
-                    .unwrap_or("".to_string());
+                    .map_err(|why| why.to_string())?;
 
     return get_gamma_epsilon_product_from_input(input);
 }
 
-fn get_gamma_epsilon_product_from_input(input: String) -> i32
-{
-    let bit_counters = count_bits(input);
-
-    let mut gamma   = String::new();
-    let mut epsilon = String::new();
+const MAX_BIT_WIDTH: usize = 64;
 
-    for bit in bit_counters
-    {
-        gamma   += if bit.0 > bit.1 { "0" } else { "1"};
-        epsilon += if bit.0 < bit.1 { "0" } else { "1"};
-    }
-
-    let gamma   = isize::from_str_radix(gamma.as_str(),   2).unwrap_or(0);
-    let epsilon = isize::from_str_radix(epsilon.as_str(), 2).unwrap_or(0);
+fn get_gamma_epsilon_product_from_input(input: String) -> Result<u128, String>
+{
+    let report = DiagnosticReport::parse(&input).map_err(|why| format!("Invalid report: {}", why))?;
 
-    return (gamma * epsilon) as i32;
+    let (gamma, epsilon) = report.get_gamma_epsilon();
+    return Ok(gamma as u128 * epsilon as u128);
 }
 
-fn count_bits(input: String) -> Vec<(usize, usize)>
+struct DiagnosticReport
 {
-    let mut result: Vec<(usize, usize)> = Vec::new();
+    values: Vec<u64>,
+    bit_width: usize
+}
 
-    for line in input.lines()
+impl DiagnosticReport
+{
+    pub fn parse(input: &str) -> Result<Self, String>
     {
-        let mut chars = line.chars();
-        let num_chars = line.len();
+        let mut report = Self{ values: Vec::new(), bit_width: 0 };
 
-        if result.is_empty()
+        for (i, line) in input.lines().enumerate()
         {
-            result.resize_with(num_chars, Default::default);
+            let line = line.trim();
+            if line.is_empty() { continue; }
+
+            if report.values.is_empty()
+            {
+                report.bit_width = line.len();
+                if report.bit_width > MAX_BIT_WIDTH
+                {
+                    return Err(format!("Line {}: more than {} bits", i+1, MAX_BIT_WIDTH));
+                }
+            }
+            else if line.len() != report.bit_width
+            {
+                return Err(format!("Line {}: expected {} bits, found {}", i+1, report.bit_width, line.len()));
+            }
+
+            let value = u64::from_str_radix(line, 2).map_err(|why| format!("Line {}: {}", i+1, why))?;
+            report.values.push(value);
         }
 
-        for i in 0..num_chars
+        return Ok(report);
+    }
+
+    fn get_mask(&self) -> u64
+    {
+        if self.bit_width == MAX_BIT_WIDTH { return u64::MAX; }
+        return (1 << self.bit_width) - 1;
+    }
+
+    // Number of 1s in each column, indexed by bit position (0 is the least significant)
+    pub fn count_ones(&self) -> Vec<usize>
+    {
+        let mut result = vec![0; self.bit_width];
+        for value in &self.values
         {
-            match chars.next()
+            let mut bits = *value;
+            while bits != 0
             {
-                Some(c) => match c
-                {
-                    '0' => result[i].0 += 1,
-                    '1' => result[i].1 += 1,
-                    _ => ()
-                },
-                _ => ()
+                let bit = bits.trailing_zeros() as usize;
+                result[bit] += 1;
+                bits &= bits - 1; // Clear the lowest set bit
             }
         }
+        return result;
     }
 
-    return result;
+    // Gamma takes the most common bit of each column, epsilon the least common.
+    // Ties count as 1 for gamma.
+    pub fn get_gamma_epsilon(&self) -> (u64, u64)
+    {
+        let num_values = self.values.len();
+
+        let mut gamma: u64 = 0;
+        for (bit, ones) in self.count_ones().iter().enumerate()
+        {
+            if *ones * 2 >= num_values { gamma |= 1 << bit; }
+        }
+
+        let epsilon = !gamma & self.get_mask();
+        return (gamma, epsilon);
+    }
 }
 
 #[cfg(test)]
@@ -73,7 +109,7 @@ mod tests
 
         let input = input.unwrap();
 
-        assert_eq!(get_gamma_epsilon_product_from_input(input), 198);
+        assert_eq!(get_gamma_epsilon_product_from_input(input), Ok(198));
     }
 
     #[test]
@@ -98,4 +134,64 @@ mod tests
 
         assert_eq!(decimal, 21);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_report()
+    {
+        let input  = parse_input_at("src/inputs/day_3_example.txt").unwrap();
+        let report = DiagnosticReport::parse(&input).unwrap();
+
+        assert_eq!(report.bit_width, 5);
+        assert_eq!(report.values.len(), 12);
+        assert_eq!(report.values[0], 0b00100);
+        assert_eq!(report.count_ones(), vec![5, 7, 8, 5, 7]);
+        assert_eq!(report.get_gamma_epsilon(), (22, 9));
+    }
+
+    #[test]
+    fn test_invalid_reports()
+    {
+        assert!(DiagnosticReport::parse("0101\n011").is_err());
+        assert!(DiagnosticReport::parse("0120").is_err());
+        assert!(DiagnosticReport::parse(&"1".repeat(MAX_BIT_WIDTH + 1)).is_err());
+
+        assert_eq!(get_gamma_epsilon_product_from_input("0101\n011".to_string()),
+                   Err("Invalid report: Line 2: expected 4 bits, found 3".to_string()));
+    }
+
+    #[test]
+    fn test_64_bit_report()
+    {
+        let high_half = format!("{}{}", "1".repeat(32), "0".repeat(32));
+        let low_half  = format!("{}{}", "0".repeat(32), "1".repeat(32));
+        let input = format!("{}\n{}\n{}\n", high_half, high_half, low_half);
+
+        let report = DiagnosticReport::parse(&input).unwrap();
+        assert_eq!(report.bit_width, 64);
+        assert_eq!(report.get_gamma_epsilon(), (0xFFFF_FFFF_0000_0000, 0x0000_0000_FFFF_FFFF));
+    }
+
+    #[test]
+    fn test_large_report()
+    {
+        let mut input = String::new();
+        for i in 0..1_000_000_u32
+        {
+            input += &format!("{:020b}\n", i);
+        }
+
+        let report = DiagnosticReport::parse(&input).unwrap();
+        let ones   = report.count_ones();
+
+        // Bit 0 alternates, bit 19 is only set from 524288 on
+        assert_eq!(ones[0],  500_000);
+        assert_eq!(ones[19], 1_000_000 - 524_288);
+        assert_eq!(report.get_gamma_epsilon().0 & 0b1, 1);
+    }
+
+    #[test]
+    fn test_full_input()
+    {
+        assert_eq!(day_3_1(), Ok(2724524));
+    }
+}
@@ -1,64 +1,63 @@
 use crate::
 {
     input_parser::*,
+    bingo_board::{WinRule, DEFAULT_WIN_RULES},
+    bingo_game::{BingoGame, GameReport}
 };
 
-pub fn day_4_1() -> i32
+pub fn day_4_1() -> Result<i32, String>
 {
-    let input = parse_input_at("src/inputs/day_4_input.txt");
-    assert!(input.is_ok());
-    let input = input.unwrap();
+    return day_4_1_with_rules(&DEFAULT_WIN_RULES);
+}
 
-    return get_first_winning_score(input);
+pub fn day_4_2() -> Result<i32, String>
+{
+    return day_4_2_with_rules(&DEFAULT_WIN_RULES);
 }
 
-pub fn day_4_2() -> i32
+pub fn day_4_1_with_rules(rules: &[WinRule]) -> Result<i32, String>
 {
-    let input = parse_input_at("src/inputs/day_4_input.txt");
-    assert!(input.is_ok());
-    let input = input.unwrap();
+    let input = parse_input_at("src/inputs/day_4_input.txt").map_err(|why| why.to_string())?;
 
-    return get_last_winning_score(input);
+    return get_first_winning_score_with_rules(input, rules);
 }
 
-fn get_first_winning_score(input: String) -> i32
+pub fn day_4_2_with_rules(rules: &[WinRule]) -> Result<i32, String>
 {
-    let numbers = parse_drawn_nums(&input);
-    let mut boards = parse_boards(&input);
+    let input = parse_input_at("src/inputs/day_4_input.txt").map_err(|why| why.to_string())?;
 
-    for num in numbers
-    {
-        for i in 0..boards.len()
-        {
-            if boards[i].check_and_mark(num)
-            {
-                return num * boards[i].get_unmarked_sum();
-            }
-        }
-    }
-    return 0;
+    return get_last_winning_score_with_rules(input, rules);
 }
 
-fn get_last_winning_score(input: String) -> i32
+fn play_game(input: &String, rules: &[WinRule]) -> Result<GameReport, String>
 {
-    let numbers = parse_drawn_nums(&input);
-    let mut boards = parse_boards(&input);
+    let mut game = BingoGame::new_from_input(input)?;
+    game.set_win_rules(rules)?;
+    return Ok(game.play());
+}
 
-    let mut last_winning_number     = 0;
-    let mut last_winning_board_idx  = 0;
-    for num in numbers
-    {
-        for i in 0..boards.len()
-        {
-            if !boards[i].get_bingo_state() &&
-                boards[i].check_and_mark(num)
-            {
-                last_winning_number     = num;
-                last_winning_board_idx  = i;
-            }
-        }
-    }
-    return last_winning_number * boards[last_winning_board_idx].get_unmarked_sum();
+fn get_first_winning_score(input: String) -> Result<i32, String>
+{
+    return get_first_winning_score_with_rules(input, &DEFAULT_WIN_RULES);
+}
+
+fn get_last_winning_score(input: String) -> Result<i32, String>
+{
+    return get_last_winning_score_with_rules(input, &DEFAULT_WIN_RULES);
+}
+
+fn get_first_winning_score_with_rules(input: String, rules: &[WinRule]) -> Result<i32, String>
+{
+    return Ok( play_game(&input, rules)?.get_first_winner()
+                                        .map(|winner| winner.score)
+                                        .unwrap_or(0) );
+}
+
+fn get_last_winning_score_with_rules(input: String, rules: &[WinRule]) -> Result<i32, String>
+{
+    return Ok( play_game(&input, rules)?.get_last_winner()
+                                        .map(|winner| winner.score)
+                                        .unwrap_or(0) );
 }
 
 
@@ -66,6 +65,7 @@ fn get_last_winning_score(input: String) -> i32
 mod tests
 {
     use super::*;
+    use crate::bingo_board::{BingoBoard, BingoWin};
     #[test]
     fn test_row_bingo()
     {
@@ -73,7 +73,7 @@ mod tests
         assert!(input.is_ok());
         let input = input.unwrap();
 
-        let mut boards = parse_boards(&input);
+        let mut boards = parse_boards(&input).unwrap();
 
         boards[0].check_and_mark(22);
         boards[0].check_and_mark(13);
@@ -89,7 +89,7 @@ mod tests
         assert!(input.is_ok());
         let input = input.unwrap();
 
-        let mut boards = parse_boards(&input);
+        let mut boards = parse_boards(&input).unwrap();
 
         boards[0].check_and_mark(22);
         boards[0].check_and_mark(8);
@@ -107,7 +107,7 @@ mod tests
 
         let score = get_first_winning_score(input);
 
-        assert_eq!(score, 4512);
+        assert_eq!(score, Ok(4512));
     }
 
     #[test]
@@ -119,6 +119,153 @@ mod tests
 
         let score = get_last_winning_score(input);
 
-        assert_eq!(score, 1924);
+        assert_eq!(score, Ok(1924));
+    }
+
+    #[test]
+    fn test_3x3_boards()
+    {
+        let input = "5,1,9,2,3\n\n1 2 3\n4 5 6\n7 8 9\n\n9 8 7\n6 5 4\n3 2 1\n".to_string();
+
+        assert_eq!(get_first_winning_score(input.clone()), Ok(3 * 25));
+        assert_eq!(get_last_winning_score(input),          Ok(3 * 25));
+    }
+
+    #[test]
+    fn test_7x7_column_bingo()
+    {
+        let mut input = "3,10,17,24,31,38,45\n\n".to_string();
+        for y in 0..7
+        {
+            let row: Vec<String> = (0..7).map(|x| (y * 7 + x).to_string()).collect();
+            input += &row.join(" ");
+            input += "\n";
+        }
+
+        let unmarked_sum = (0..49).sum::<i32>() - (3+10+17+24+31+38+45);
+        assert_eq!(get_first_winning_score(input), Ok(45 * unmarked_sum));
+    }
+
+    fn build_3x3_board() -> BingoBoard
+    {
+        let mut board = BingoBoard::new(3, 3);
+        for i in 0..9
+        {
+            board.set_cell_at(i % 3, i / 3, i as i32 + 1);
+        }
+        return board;
+    }
+
+    #[test]
+    fn test_diagonal_bingo()
+    {
+        let mut board = build_3x3_board();
+        assert!(!board.check_and_mark(1));
+        assert!(!board.check_and_mark(5));
+        assert!(!board.check_and_mark(9)); // Not a win by default
+
+        let mut board = build_3x3_board();
+        board.set_win_rules(&[WinRule::Rows, WinRule::Columns, WinRule::Diagonals]).unwrap();
+        board.mark(1);
+        board.mark(5);
+        assert_eq!(board.mark(9), Some(BingoWin::Diagonal));
+
+        board.mark(3);
+        assert_eq!(board.mark(7), Some(BingoWin::AntiDiagonal));
+    }
+
+    #[test]
+    fn test_four_corners_bingo()
+    {
+        let mut board = build_3x3_board();
+        board.set_win_rules(&[WinRule::FourCorners]).unwrap();
+
+        assert_eq!(board.mark(1), None);
+        assert_eq!(board.mark(2), None);
+        assert_eq!(board.mark(3), None); // Full row, but rows don't count
+        assert_eq!(board.mark(7), None);
+        assert_eq!(board.mark(9), Some(BingoWin::FourCorners));
+    }
+
+    #[test]
+    fn test_blackout_bingo()
+    {
+        let mut board = build_3x3_board();
+        board.set_win_rules(&[WinRule::Blackout]).unwrap();
+
+        for i in 1..9
+        {
+            assert_eq!(board.mark(i), None);
+        }
+        assert_eq!(board.mark(9), Some(BingoWin::Blackout));
+        assert_eq!(board.get_unmarked_sum(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_custom_pattern_bingo()
+    {
+        let mut board = build_3x3_board();
+        let cross = WinRule::pattern_from_str(".X.\n\
+                                               XXX\n\
+                                               .X.");
+        board.set_win_rules(&[WinRule::Blackout, cross]).unwrap();
+
+        for i in [2, 4, 6, 8]
+        {
+            assert_eq!(board.mark(i), None);
+        }
+        assert_eq!(board.mark(5), Some(BingoWin::Pattern(0)));
+    }
+
+    #[test]
+    fn test_rules_applied_mid_game()
+    {
+        let mut board = build_3x3_board();
+        board.mark(1);
+        board.mark(5);
+
+        board.set_win_rules(&[WinRule::Diagonals]).unwrap();
+        assert_eq!(board.mark(9), Some(BingoWin::Diagonal));
+    }
+
+    #[test]
+    fn test_diagonals_need_square_boards()
+    {
+        let mut board = BingoBoard::new(2, 3);
+        board.set_win_rules(&[WinRule::Diagonals]).unwrap();
+        for i in 0..6
+        {
+            board.set_cell_at(i % 3, i / 3, i as i32);
+            assert!(!board.check_and_mark(i as i32));
+        }
+    }
+
+    #[test]
+    fn test_example_with_blackout()
+    {
+        let input = parse_input_at("src/inputs/day_4_example.txt").unwrap();
+
+        let report = play_game(&input, &[WinRule::Blackout]).unwrap();
+
+        // Every number on the boards gets drawn eventually
+        let order: Vec<(usize, usize)> = report.winners.iter()
+                                                       .map(|winner| (winner.board_idx, winner.turn))
+                                                       .collect();
+        assert_eq!(order, vec![(1, 25), (2, 26), (0, 27)]);
+        assert!(report.winners.iter().all(|winner| winner.win == BingoWin::Blackout && winner.score == 0));
+    }
+
+    #[test]
+    fn test_example_with_diagonals()
+    {
+        let input = parse_input_at("src/inputs/day_4_example.txt").unwrap();
+        let rules = [WinRule::Rows, WinRule::Columns, WinRule::Diagonals];
+
+        // Third board's anti-diagonal (4, 9, 23, 11, 2) beats its first row
+        let first = *play_game(&input, &rules).unwrap().get_first_winner().unwrap();
+        assert_eq!(first.board_idx, 2);
+        assert_eq!(first.turn, 8);
+        assert_eq!(first.win, BingoWin::AntiDiagonal);
+        assert_eq!(first.score, 2 * 247);
+    }
+}
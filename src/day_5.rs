@@ -1,10 +1,6 @@
 use crate::input_parser::parse_input_at;
 
-use std::
-{
-    collections::HashMap,
-    cmp::{max, min}
-};
+use std::collections::{HashMap, HashSet};
 
 pub fn day_5_1() -> usize
 {
@@ -15,6 +11,23 @@ pub fn day_5_1() -> usize
     return count_overlapped_cells(cells);
 }
 
+pub fn day_5_2() -> usize
+{
+    let input = parse_input_at("src/inputs/day_5_input.txt")
+                    .unwrap_or("".to_string());
+    let cells = map_segments(&parse_segments(&input), SegmentKinds::WithDiagonals);
+
+    return count_overlapped_cells(cells);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentKinds
+{
+    Orthogonal,    // Horizontal and vertical only
+    WithDiagonals, // Plus 45 degree diagonals
+    Any            // Any slope
+}
+
 fn count_overlapped_cells(cells: HashMap<(usize, usize), usize>) -> usize
 {
     return cells.values()
@@ -24,29 +37,104 @@ fn count_overlapped_cells(cells: HashMap<(usize, usize), usize>) -> usize
 
 fn map_line_segments(input: String) -> HashMap<(usize, usize), usize>
 {
-    let mut segments = HashMap::new();
+    return map_segments(&parse_segments(&input), SegmentKinds::Orthogonal);
+}
+
+fn map_segments(segments: &[LineSegment], kinds: SegmentKinds) -> HashMap<(usize, usize), usize>
+{
+    let mut cells = HashMap::new();
 
-    for entry in input.lines()
+    for segment in segments
     {
-        let segment = parse_input_entry(entry);
+        let is_included = match kinds
+        {
+            SegmentKinds::Orthogonal    => segment.is_horizontal() || segment.is_vertical(),
+            SegmentKinds::WithDiagonals => segment.is_horizontal() || segment.is_vertical() || segment.is_diagonal(),
+            SegmentKinds::Any           => true
+        };
+        if !is_included { continue; }
 
-        if segment.is_horizontal()
+        for cell in segment.get_cells()
         {
-            for i in segment.start.0 .. segment.end.0+1
+            *cells.entry(cell).or_insert(0) += 1;
+        }
+    }
+
+    return cells;
+}
+
+fn gcd(a: usize, b: usize) -> usize
+{
+    return if b == 0 { a } else { gcd(b, a % b) };
+}
+
+// Smallest (top left, bottom right) box containing every covered cell
+fn get_bounding_box(cells: &HashMap<(usize, usize), usize>) -> ((usize, usize), (usize, usize))
+{
+    let min_x = cells.keys().map(|p| p.0).min().unwrap_or(0);
+    let min_y = cells.keys().map(|p| p.1).min().unwrap_or(0);
+    let max_x = cells.keys().map(|p| p.0).max().unwrap_or(0);
+    let max_y = cells.keys().map(|p| p.1).max().unwrap_or(0);
+    return ((min_x, min_y), (max_x, max_y));
+}
+
+// Puzzle style diagram of the (inclusive) box: '.' for empty cells, and the
+// number of lines covering the rest. Only one character per cell, so counts
+// above 9 show as '+'.
+fn render_diagram(cells: &HashMap<(usize, usize), usize>,
+                  top_left: (usize, usize),
+                  bottom_right: (usize, usize))
+-> String
+{
+    let mut diagram = String::new();
+    for y in top_left.1 ..= bottom_right.1
+    {
+        for x in top_left.0 ..= bottom_right.0
+        {
+            diagram.push( match cells.get(&(x, y)).copied().unwrap_or(0)
             {
-                *segments.entry((i, segment.start.1)).or_insert(0) += 1;
-            }
+                0             => '.',
+                count @ 1..=9 => char::from_digit(count as u32, 10).unwrap(),
+                _             => '+'
+            });
         }
-        else if segment.is_vertical()
+        diagram.push('\n');
+    }
+    return diagram;
+}
+
+fn print_diagram(cells: &HashMap<(usize, usize), usize>)
+{
+    let (top_left, bottom_right) = get_bounding_box(cells);
+    print!("{}", render_diagram(cells, top_left, bottom_right));
+}
+
+// Reverse of `render_diagram`, `top_left` being the position of the first
+// character. A '+' is read as 10.
+fn parse_diagram(diagram: &str, top_left: (usize, usize)) -> HashMap<(usize, usize), usize>
+{
+    let mut cells = HashMap::new();
+    for (dy, line) in diagram.lines().enumerate()
+    {
+        for (dx, c) in line.chars().enumerate()
         {
-            for i in segment.start.1 .. segment.end.1+1
+            let count = match c
             {
-                *segments.entry((segment.start.0, i)).or_insert(0) += 1;
-            }
+                '+' => 10,
+                 _  => c.to_digit(10).unwrap_or(0) as usize
+            };
+            if count > 0 { cells.insert((top_left.0 + dx, top_left.1 + dy), count); }
         }
     }
+    return cells;
+}
 
-    return segments;
+fn parse_segments(input: &str) -> Vec<LineSegment>
+{
+    return input.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(parse_input_entry)
+                .collect();
 }
 
 fn parse_input_entry(entry: &str) -> LineSegment
@@ -55,26 +143,18 @@ fn parse_input_entry(entry: &str) -> LineSegment
     let (x0, y0)     = start.split_once(',').unwrap_or( ("0", "0") );
     let (x1, y1)     = end.split_once(',').unwrap_or( ("0", "0") );
 
-    let x0 = x0.parse::<usize>().unwrap_or(0);
-    let x1 = x1.parse::<usize>().unwrap_or(0);
-    let y0 = y0.parse::<usize>().unwrap_or(0);
-    let y1 = y1.parse::<usize>().unwrap_or(0);
-
-    return LineSegment
-    {
-        start:
-        (
-            min(x0, x1),
-            min(y0, y1)
-        ),
-        end:
-        (
-            max(x0, x1),
-            max(y0, y1)
-        )
-    };
+    let x0 = x0.trim().parse::<usize>().unwrap_or(0);
+    let x1 = x1.trim().parse::<usize>().unwrap_or(0);
+    let y0 = y0.trim().parse::<usize>().unwrap_or(0);
+    let y1 = y1.trim().parse::<usize>().unwrap_or(0);
+
+    // Always store the segment left to right (top to bottom if vertical)
+    let (start, end) = if (x0, y0) <= (x1, y1) { ((x0, y0), (x1, y1)) } else { ((x1, y1), (x0, y0)) };
+
+    return LineSegment{ start, end };
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct LineSegment
 {
     start: (usize, usize),
@@ -85,6 +165,220 @@ impl LineSegment
 {
     pub fn is_horizontal(&self) -> bool { self.start.1 == self.end.1 }
     pub fn is_vertical(&self)   -> bool { self.start.0 == self.end.0 }
+    pub fn is_diagonal(&self)   -> bool
+    {
+        return !self.is_horizontal() && self.end.0 - self.start.0 == self.start.1.abs_diff(self.end.1);
+    }
+
+    // Smallest lattice step along the segment, and how many of them it takes
+    fn get_step(&self) -> ((i64, i64), usize)
+    {
+        let dx = self.end.0 as i64 - self.start.0 as i64;
+        let dy = self.end.1 as i64 - self.start.1 as i64;
+
+        let num_steps = gcd(dx.unsigned_abs() as usize, dy.unsigned_abs() as usize);
+        if num_steps == 0 { return ((0, 0), 0); } // Single point
+
+        return ((dx / num_steps as i64, dy / num_steps as i64), num_steps);
+    }
+
+    // Every integer point the segment goes through, ends included
+    pub fn get_cells(&self) -> Vec<(usize, usize)>
+    {
+        let ((step_x, step_y), num_steps) = self.get_step();
+
+        let mut cells = Vec::with_capacity(num_steps + 1);
+        for i in 0..=num_steps as i64
+        {
+            let x = self.start.0 as i64 + i * step_x;
+            let y = self.start.1 as i64 + i * step_y;
+            cells.push( (x as usize, y as usize) );
+        }
+        return cells;
+    }
+
+    // The infinite line the segment lies on, and the range it covers along it
+    fn get_support(&self) -> Option<LineSupport>
+    {
+        let (x0, y0) = (self.start.0 as i64, self.start.1 as i64);
+        let (x1, y1) = (self.end.0   as i64, self.end.1   as i64);
+
+        if self.is_vertical()   { return Some( (LineFamily::Vertical,   x0, (y0, y1)) ); }
+        if self.is_horizontal() { return Some( (LineFamily::Horizontal, y0, (x0, x1)) ); }
+        if self.is_diagonal()
+        {
+            return if y1 > y0 { Some( (LineFamily::Falling, y0 - x0, (x0, x1)) ) }
+                   else       { Some( (LineFamily::Rising,  y0 + x0, (x0, x1)) ) };
+        }
+        return None;
+    }
+}
+
+// (family, key, (first t, last t))
+type LineSupport = (LineFamily, i64, (i64, i64));
+
+// Every supported line is `key = f(x, y)` for one of these, and points along
+// it are identified by `t`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum LineFamily
+{
+    Horizontal, // key = y,     t = x
+    Vertical,   // key = x,     t = y
+    Falling,    // key = y - x, t = x (y grows with x, so it goes down the diagram)
+    Rising      // key = y + x, t = x
+}
+
+const LINE_FAMILIES: [LineFamily; 4] = [LineFamily::Horizontal, LineFamily::Vertical,
+                                        LineFamily::Falling,    LineFamily::Rising];
+
+impl LineFamily
+{
+    fn get_key_and_t(&self, (x, y): (i64, i64)) -> (i64, i64)
+    {
+        return match self
+        {
+            LineFamily::Horizontal => (y,     x),
+            LineFamily::Vertical   => (x,     y),
+            LineFamily::Falling    => (y - x, x),
+            LineFamily::Rising     => (y + x, x)
+        };
+    }
+}
+
+// Lattice point where two lines of different families meet, if any
+fn intersect((family_a, key_a): (LineFamily, i64), (family_b, key_b): (LineFamily, i64)) -> Option<(i64, i64)>
+{
+    use LineFamily::*;
+    return match (family_a, family_b)
+    {
+        (Horizontal, Vertical) => Some( (key_b, key_a) ),
+        (Horizontal, Falling)  => Some( (key_a - key_b, key_a) ),
+        (Horizontal, Rising)   => Some( (key_b - key_a, key_a) ),
+        (Vertical,   Falling)  => Some( (key_a, key_b + key_a) ),
+        (Vertical,   Rising)   => Some( (key_a, key_b - key_a) ),
+        (Falling,    Rising)   =>
+        {
+            // y = x + a and y = b - x only meet on the grid if b - a is even
+            if (key_b - key_a) % 2 != 0 { return None; }
+            let x = (key_b - key_a) / 2;
+            Some( (x, x + key_a) )
+        },
+        _ if family_a == family_b => None,
+        _ => intersect((family_b, key_b), (family_a, key_a))
+    };
+}
+
+// Counts the cells covered by at least two segments without visiting every
+// cell, so huge coordinates are fine:
+//  - Segments on the same line are merged as intervals
+//  - Segments crossing each other are found sweeping left to right, and only
+//    the crossing points are considered
+// Like `SegmentKinds::WithDiagonals`, segments of any other slope are ignored:
+// map them with `SegmentKinds::Any` instead.
+fn count_overlaps_by_intervals(segments: &[LineSegment], include_diagonals: bool) -> usize
+{
+    let mut supports = Vec::new();
+    for segment in segments
+    {
+        if !include_diagonals && segment.is_diagonal() { continue; }
+        if let Some(support) = segment.get_support() { supports.push( (support, *segment) ); }
+    }
+
+    // Overlaps between segments on the same line
+    let mut intervals_per_line: HashMap<(LineFamily, i64), Vec<(i64, i64)>> = HashMap::new();
+    for ((family, key, range), _) in &supports
+    {
+        intervals_per_line.entry((*family, *key)).or_default().push(*range);
+    }
+
+    let mut count: i64 = 0;
+    let mut overlaps_per_line: HashMap<(LineFamily, i64), Vec<(i64, i64)>> = HashMap::new();
+    for (line, intervals) in intervals_per_line
+    {
+        let overlaps = get_overlapping_ranges(&intervals);
+        count += overlaps.iter().map(|(a, b)| b - a + 1).sum::<i64>();
+        if !overlaps.is_empty() { overlaps_per_line.insert(line, overlaps); }
+    }
+
+    // Crossings between segments on different lines
+    let mut crossings = HashSet::new();
+    supports.sort_unstable_by_key(|(_, segment)| segment.start.0);
+    let mut active: Vec<&(LineSupport, LineSegment)> = Vec::new();
+    for current in &supports
+    {
+        let x = current.1.start.0;
+        active.retain(|(_, segment)| segment.end.0 >= x);
+
+        let ((family, key, range), _) = current;
+        for ((other_family, other_key, other_range), _) in &active
+        {
+            let point = match intersect((*family, *key), (*other_family, *other_key))
+            {
+                Some(point) => point,
+                None        => continue
+            };
+
+            let t       = family.get_key_and_t(point).1;
+            let other_t = other_family.get_key_and_t(point).1;
+            if t >= range.0 && t <= range.1 && other_t >= other_range.0 && other_t <= other_range.1
+            {
+                crossings.insert(point);
+            }
+        }
+        active.push(current);
+    }
+
+    // A crossing may already be part of one (or more!) same-line overlaps
+    for point in crossings
+    {
+        let mut times_counted = 0;
+        for family in LINE_FAMILIES
+        {
+            let (key, t) = family.get_key_and_t(point);
+            let overlaps = match overlaps_per_line.get(&(family, key))
+            {
+                Some(overlaps) => overlaps,
+                None           => continue
+            };
+            if overlaps.iter().any(|(a, b)| t >= *a && t <= *b) { times_counted += 1; }
+        }
+        count += if times_counted == 0 { 1 } else { 1 - times_counted };
+    }
+
+    return count as usize;
+}
+
+// Sub-ranges covered by at least two of the given inclusive ranges
+fn get_overlapping_ranges(ranges: &[(i64, i64)]) -> Vec<(i64, i64)>
+{
+    let mut events = Vec::with_capacity(ranges.len() * 2);
+    for (a, b) in ranges
+    {
+        events.push( (*a,    1) );
+        events.push( (*b+1, -1) );
+    }
+    events.sort_unstable();
+
+    let mut overlaps: Vec<(i64, i64)> = Vec::new();
+    let mut coverage = 0;
+    let mut overlap_start = 0;
+    for (t, delta) in events
+    {
+        let was_overlapping = coverage >= 2;
+        coverage += delta;
+
+        if !was_overlapping && coverage >= 2 { overlap_start = t; }
+        if was_overlapping && coverage < 2 && t > overlap_start
+        {
+            // Merge with the previous overlap if they touch
+            match overlaps.last_mut()
+            {
+                Some(last) if last.1 + 1 == overlap_start => last.1 = t - 1,
+                _ => overlaps.push( (overlap_start, t - 1) )
+            }
+        }
+    }
+    return overlaps;
 }
 
 #[cfg(test)]
@@ -132,4 +426,187 @@ mod tests
 
         assert_eq!(count, 5);
     }
-}
\ No newline at end of file
+
+    // Reference for the interval counter: visits every cell
+    fn count_overlaps_by_cells(segments: &[LineSegment], include_diagonals: bool) -> usize
+    {
+        let kinds = if include_diagonals { SegmentKinds::WithDiagonals } else { SegmentKinds::Orthogonal };
+        return count_overlapped_cells( map_segments(segments, kinds) );
+    }
+
+    #[test]
+    fn test_interval_count_matches_cell_map()
+    {
+        for path in ["src/inputs/day_5_example.txt", "src/inputs/day_5_input.txt"]
+        {
+            let input    = parse_input_at(path).unwrap();
+            let segments = parse_segments(&input);
+
+            let expected = count_overlapped_cells(map_line_segments(input));
+            assert_eq!(count_overlaps_by_intervals(&segments, false), expected);
+        }
+    }
+
+    #[test]
+    fn test_interval_count_with_diagonals()
+    {
+        let input    = parse_input_at("src/inputs/day_5_example.txt").unwrap();
+        let segments = parse_segments(&input);
+        assert_eq!(count_overlaps_by_intervals(&segments, true), 12);
+
+        let input    = parse_input_at("src/inputs/day_5_input.txt").unwrap();
+        let segments = parse_segments(&input);
+        assert_eq!(count_overlaps_by_intervals(&segments, true), count_overlaps_by_cells(&segments, true));
+    }
+
+    #[test]
+    fn test_crossings_inside_overlaps()
+    {
+        // Two overlapping rows and two overlapping columns meeting at (5,5),
+        // plus a diagonal through it
+        let input = "0,5 -> 6,5\n3,5 -> 9,5\n5,0 -> 5,7\n5,4 -> 5,9\n0,0 -> 9,9\n2,8 -> 8,2";
+        let segments = parse_segments(input);
+
+        assert_eq!(count_overlaps_by_intervals(&segments, true), count_overlaps_by_cells(&segments, true));
+        assert_eq!(count_overlaps_by_intervals(&segments, false), count_overlaps_by_cells(&segments, false));
+    }
+
+    #[test]
+    fn test_diagonals_crossing_between_cells()
+    {
+        // These two cross at (0.5, 0.5)
+        let segments = parse_segments("0,0 -> 1,1\n1,0 -> 0,1");
+        assert_eq!(count_overlaps_by_intervals(&segments, true), 0);
+    }
+
+    #[test]
+    fn test_other_slopes_are_ignored()
+    {
+        // The 2:1 segment crosses the row at (2,1)
+        let segments = parse_segments("0,0 -> 4,2\n0,1 -> 4,1");
+        assert_eq!(count_overlaps_by_intervals(&segments, true), 0);
+        assert_eq!(count_overlapped_cells( map_segments(&segments, SegmentKinds::Any) ), 1);
+    }
+
+    #[test]
+    fn test_huge_segments()
+    {
+        let input = "0,0 -> 5000000,0\n\
+                     2500000,0 -> 9000000,0\n\
+                     3000000,0 -> 3000000,4000000\n\
+                     7000000,1 -> 7000000,9";
+        let segments = parse_segments(input);
+
+        // Only the shared part of the first two; the crossing is already in it
+        assert_eq!(count_overlaps_by_intervals(&segments, false), 2500001);
+    }
+
+    #[test]
+    fn test_get_overlapping_ranges()
+    {
+        assert_eq!(get_overlapping_ranges(&[(0, 5), (3, 8)]),         vec![(3, 5)]);
+        assert_eq!(get_overlapping_ranges(&[(0, 5), (6, 8)]),         vec![]);
+        assert_eq!(get_overlapping_ranges(&[(0, 9), (2, 3), (4, 5)]), vec![(2, 5)]);
+        assert_eq!(get_overlapping_ranges(&[(1, 1), (1, 1), (1, 1)]), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_render_example_diagram()
+    {
+        let input    = parse_input_at("src/inputs/day_5_example.txt").unwrap();
+        let expected = parse_input_at("src/inputs/day_5_example_diagram.txt").unwrap();
+        let cells    = map_line_segments(input);
+
+        assert_eq!(render_diagram(&cells, (0,0), (9,9)).trim_end(), expected.trim_end());
+    }
+
+    #[test]
+    fn test_parse_example_diagram()
+    {
+        let input   = parse_input_at("src/inputs/day_5_example.txt").unwrap();
+        let diagram = parse_input_at("src/inputs/day_5_example_diagram.txt").unwrap();
+        let cells   = map_line_segments(input);
+
+        let expected = parse_diagram(&diagram, (0,0));
+        for y in 0..10
+        {
+            for x in 0..10
+            {
+                assert_eq!(cells.get(&(x,y)), expected.get(&(x,y)), "Mismatch at {},{}", x, y);
+            }
+        }
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn test_render_sub_box()
+    {
+        let input = parse_input_at("src/inputs/day_5_example.txt").unwrap();
+        let cells = map_line_segments(input);
+
+        assert_eq!(get_bounding_box(&cells), ((0,0), (9,9)));
+        assert_eq!(render_diagram(&cells, (6,3), (9,4)), ".1..\n1211\n");
+        assert_eq!(parse_diagram(".1..\n1211\n", (6,3)).get(&(7,4)), Some(&2));
+    }
+
+    #[test]
+    fn test_render_large_counts()
+    {
+        let cells = HashMap::from([((0,0), 12), ((1,0), 3)]);
+        assert_eq!(render_diagram(&cells, (0,0), (2,0)), "+3.\n");
+    }
+
+    #[test]
+    fn test_second_example()
+    {
+        let input = parse_input_at("src/inputs/day_5_example.txt").unwrap();
+        let cells = map_segments(&parse_segments(&input), SegmentKinds::WithDiagonals);
+
+        assert_eq!(count_overlapped_cells(cells), 12);
+    }
+
+    #[test]
+    fn test_any_slope_matches_puzzle_input()
+    {
+        // The puzzle only has horizontal, vertical and 45 degree lines
+        let input    = parse_input_at("src/inputs/day_5_input.txt").unwrap();
+        let segments = parse_segments(&input);
+
+        assert_eq!(map_segments(&segments, SegmentKinds::Any),
+                   map_segments(&segments, SegmentKinds::WithDiagonals));
+    }
+
+    #[test]
+    fn test_lattice_points()
+    {
+        let segment = parse_input_entry("6,4 -> 0,1");
+        assert_eq!(segment.get_cells(), vec![(0,1), (2,2), (4,3), (6,4)]);
+
+        // 3 and 7 are coprime, so only the ends are on the grid
+        let segment = parse_input_entry("0,0 -> 3,7");
+        assert_eq!(segment.get_cells(), vec![(0,0), (3,7)]);
+
+        let segment = parse_input_entry("0,9 -> 4,3");
+        assert_eq!(segment.get_cells(), vec![(0,9), (2,6), (4,3)]);
+
+        let segment = parse_input_entry("5,5 -> 5,5");
+        assert_eq!(segment.get_cells(), vec![(5,5)]);
+    }
+
+    #[test]
+    fn test_map_any_slope()
+    {
+        let segments = parse_segments("0,0 -> 4,2\n2,0 -> 2,4\n0,3 -> 6,0");
+        let cells    = map_segments(&segments, SegmentKinds::Any);
+
+        // The vertical line meets the first one at (2,1) and the last one at (2,2)
+        assert_eq!(cells.get(&(2,1)), Some(&2));
+        assert_eq!(cells.get(&(2,2)), Some(&2));
+        assert_eq!(cells.get(&(4,1)), Some(&1));
+        assert_eq!(cells.get(&(3,1)), None);
+        assert_eq!(count_overlapped_cells(cells), 2);
+
+        let cells = map_segments(&segments, SegmentKinds::Orthogonal);
+        assert_eq!(count_overlapped_cells(cells), 0);
+    }
+}
@@ -1,61 +1,286 @@
-use crate::input_parser::parse_input_at;
+use std::
+{
+    fs,
+    io
+};
+
+use crate::
+{
+    input_parser::parse_input_at,
+    big_uint::BigUint
+};
+
+pub fn day_6_1() -> Result<usize, String>
+{
+    return simulate_input("src/inputs/day_6_input.txt", LANTERNFISH, 80);
+}
+
+pub fn day_6_2() -> Result<usize, String>
+{
+    return simulate_input("src/inputs/day_6_input.txt", LANTERNFISH, 256);
+}
+
+// Exact, no matter how many days
+pub fn day_6_after_n_days(num_days: u64) -> Result<BigUint, String>
+{
+    let population = Population::new_from_file("src/inputs/day_6_input.txt", LANTERNFISH)
+                                .map_err(|why| format!("Invalid school of fish: {}", why))?;
+    return Ok(population.get_total_after(num_days));
+}
+
+fn simulate_input(path: &str, model: SpeciesModel, num_days: usize) -> Result<usize, String>
+{
+    let mut population = Population::new_from_file(path, model).map_err(|why| format!("Invalid school of fish: {}", why))?;
+    population.simulate_n_days(num_days);
+    return Ok(population.get_total());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SpeciesModel
+{
+    // Days between births, once an adult
+    cycle_length: usize,
+    // Extra days a newborn needs before its first cycle
+    newborn_delay: usize
+}
+
+const LANTERNFISH: SpeciesModel = SpeciesModel{ cycle_length: 7, newborn_delay: 2 };
+
+impl SpeciesModel
+{
+    // Timers go from 0 to cycle_length - 1 + newborn_delay
+    pub fn get_num_slots(&self) -> usize { self.cycle_length + self.newborn_delay }
+}
 
-pub fn day_6_1() -> usize
+struct Population
 {
-    let mut day_counter_slots = get_counters_from_input("src/inputs/day_6_input.txt");
-    simulate_n_days(&mut day_counter_slots, 80);
-    return get_counters_sum(&day_counter_slots);
+    model: SpeciesModel,
+    // Slot N holds the number of fishes that have N days left to give birth.
+    counters: Vec<usize>,
+    // Days simulated so far
+    day: usize,
+    // Only recorded when requested, one entry per day
+    history: Option<Vec<PopulationRecord>>
 }
 
-pub fn day_6_2() -> usize
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PopulationRecord
 {
-    let mut day_counter_slots = get_counters_from_input("src/inputs/day_6_input.txt");
-    simulate_n_days(&mut day_counter_slots, 256);
-    return get_counters_sum(&day_counter_slots);
+    day: usize,
+    counters: Vec<usize>,
+    total: usize
 }
 
-// Slot N holds the number of fishes that have N days left to give birth.
-fn get_counters_from_input(path:&str) -> [usize; 9]
+impl Population
 {
-    let mut day_counter_slots = [0,0,0,0,0,0,0,0,0];
+    pub fn new(model: SpeciesModel) -> Self
+    {
+        assert!(model.cycle_length > 0);
+        return Self{ model, counters: vec![0; model.get_num_slots()], day: 0, history: None };
+    }
+
+    pub fn new_from_file(path: &str, model: SpeciesModel) -> Result<Self, String>
+    {
+        let input = parse_input_at(path).map_err(|why| why.to_string())?;
+
+        let mut population = Self::new(model);
+        for entry in input.trim().split(",")
+        {
+            if entry.trim().is_empty() { continue; }
 
-    let input = parse_input_at(path)
-                    .unwrap_or("".to_string());
+            let days_left = entry.trim()
+                                 .parse::<usize>()
+                                 .map_err(|why| format!("Invalid timer '{}': {}", entry, why))?;
+            population.add(days_left)?;
+        }
+        return Ok(population);
+    }
 
-    for entry in input.split(",")
+    pub fn add(&mut self, days_left: usize) -> Result<(), String>
     {
-        match entry.parse::<usize>()
+        if days_left >= self.counters.len()
         {
-            Ok(days_left) => if days_left < 7 { day_counter_slots[days_left] += 1 },
-            Err(_)        => continue
+            return Err(format!("Timer {} is out of range 0..{}", days_left, self.counters.len()));
         }
+        self.counters[days_left] += 1;
+        return Ok(());
     }
 
-    return day_counter_slots;
-}
+    pub fn get_counters(&self) -> &[usize] { &self.counters }
+    pub fn get_day(&self) -> usize { self.day }
 
-fn simulate_n_days(counters: &mut [usize;9], num_days: usize)
-{
-    for _ in 0..num_days
+    // Starts recording the population, beginning with the current day
+    pub fn record_history(&mut self)
+    {
+        self.history = Some(Vec::new());
+        self.record_day();
+    }
+
+    fn record_day(&mut self)
     {
-        let tmp = counters[0];
-        for i in 0..8
+        if let Some(history) = &mut self.history
         {
-            counters[i] = counters[i+1];
+            let total = self.counters.iter().sum();
+            history.push( PopulationRecord{ day: self.day, counters: self.counters.clone(), total } );
         }
-        counters[6] += tmp; // Old fishes get a new Time To Give Birth of 6
-        counters[8]  = tmp; // Newborns need an extra 2 days
     }
-}
 
-fn get_counters_sum(counters: &[usize; 9]) -> usize
-{
-    let mut count = 0;
-    for slot in counters
+    pub fn get_history(&self) -> &[PopulationRecord]
+    {
+        return self.history.as_deref().unwrap_or_default();
+    }
+
+    // One row per recorded day: the day, how many fishes have each timer, and the total
+    pub fn history_to_csv(&self) -> String
     {
-        count += slot;
+        let timers: Vec<String> = (0..self.counters.len()).map(|timer| format!("timer_{}", timer)).collect();
+
+        let mut csv = format!("day,{},total\n", timers.join(","));
+        for record in self.get_history()
+        {
+            let counters: Vec<String> = record.counters.iter().map(|count| count.to_string()).collect();
+            csv += &format!("{},{},{}\n", record.day, counters.join(","), record.total);
+        }
+        return csv;
+    }
+
+    pub fn save_history_csv(&self, path: &str) -> io::Result<()>
+    {
+        return fs::write(path, self.history_to_csv());
+    }
+
+    pub fn simulate_n_days(&mut self, num_days: usize)
+    {
+        for _ in 0..num_days
+        {
+            self.simulate_day();
+        }
+    }
+
+    fn simulate_day(&mut self)
+    {
+        let parents = self.counters[0];
+        self.counters.rotate_left(1); // Newborns end up in the last slot
+
+        // Parents start a new cycle
+        self.counters[self.model.cycle_length - 1] += parents;
+
+        self.day += 1;
+        self.record_day();
+    }
+
+    pub fn get_total(&self) -> usize
+    {
+        return self.counters.iter().sum();
+    }
+
+    // Population after `num_days` days, without simulating them one by one.
+    // Tries with u128 first and only falls back to big integers on overflow.
+    pub fn get_total_after(&self, num_days: u64) -> BigUint
+    {
+        if let Some(total) = self.count_after::<u128>(num_days)
+        {
+            return BigUint::from(total);
+        }
+        return self.count_after::<BigUint>(num_days).expect("Big integers can't overflow!");
+    }
+
+    // Squares the day transition matrix M to get M^N in O(log N) steps.
+    // A fish with the highest timer turns into a fish with timer `j` after
+    // `last - j` days, so column `j` of M^N is M^(last - j) times its last
+    // column. That last column is all we need to store, and squaring takes a
+    // matrix-vector product instead of a full matrix product.
+    fn count_after<T: PopulationCount>(&self, num_days: u64) -> Option<T>
+    {
+        let num_slots = self.counters.len();
+
+        // M^0 = identity
+        let mut last_column = vec![T::from_usize(0); num_slots];
+        last_column[num_slots - 1] = T::from_usize(1);
+
+        for bit in (0..u64::BITS - num_days.leading_zeros()).rev()
+        {
+            let columns = self.get_matrix_columns(&last_column)?;
+
+            // M^2N e_last = M^N (M^N e_last)
+            let mut squared = vec![T::from_usize(0); num_slots];
+            for (j, factor) in last_column.iter().enumerate()
+            {
+                for (i, value) in columns[j].iter().enumerate()
+                {
+                    squared[i] = squared[i].checked_add( &value.checked_mul(factor)? )?;
+                }
+            }
+            last_column = squared;
+
+            if (num_days >> bit) & 1 == 1
+            {
+                last_column = self.step_vector(&last_column)?;
+            }
+        }
+
+        let columns = self.get_matrix_columns(&last_column)?;
+        let mut total = T::from_usize(0);
+        for (j, &count) in self.counters.iter().enumerate()
+        {
+            if count == 0 { continue; }
+
+            let mut column_sum = T::from_usize(0);
+            for value in &columns[j]
+            {
+                column_sum = column_sum.checked_add(value)?;
+            }
+            total = total.checked_add( &column_sum.checked_mul(&T::from_usize(count))? )?;
+        }
+        return Some(total);
     }
-    return count;
+
+    // Every column of M^N, from its last one
+    fn get_matrix_columns<T: PopulationCount>(&self, last_column: &[T]) -> Option<Vec<Vec<T>>>
+    {
+        let num_slots   = self.counters.len();
+        let mut columns = vec![last_column.to_vec()];
+        for _ in 1..num_slots
+        {
+            let next = self.step_vector(columns.last().unwrap())?;
+            columns.push(next);
+        }
+        columns.reverse();
+        return Some(columns);
+    }
+
+    // Same as `simulate_day`, for any counter type
+    fn step_vector<T: PopulationCount>(&self, counters: &[T]) -> Option<Vec<T>>
+    {
+        let mut result = counters.to_vec();
+        result.rotate_left(1);
+
+        let cycle_end = self.model.cycle_length - 1;
+        result[cycle_end] = result[cycle_end].checked_add(&counters[0])?;
+        return Some(result);
+    }
+}
+
+// What the fast simulation needs from a counter, None meaning overflow
+trait PopulationCount: Clone
+{
+    fn from_usize(value: usize) -> Self;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+impl PopulationCount for u128
+{
+    fn from_usize(value: usize) -> Self { value as u128 }
+    fn checked_add(&self, other: &Self) -> Option<Self> { u128::checked_add(*self, *other) }
+    fn checked_mul(&self, other: &Self) -> Option<Self> { u128::checked_mul(*self, *other) }
+}
+
+impl PopulationCount for BigUint
+{
+    fn from_usize(value: usize) -> Self { BigUint::from(value as u128) }
+    fn checked_add(&self, other: &Self) -> Option<Self> { Some(self + other) }
+    fn checked_mul(&self, other: &Self) -> Option<Self> { Some(self * other) }
 }
 
 #[cfg(test)]
@@ -66,27 +291,201 @@ mod tests
     #[test]
     fn test_first_example()
     {
-        let mut day_counter_slots = get_counters_from_input("src/inputs/day_6_example.txt");
-        simulate_n_days(&mut day_counter_slots, 80);
-        assert_eq!(get_counters_sum(&day_counter_slots), 5934);
+        let mut population = Population::new_from_file("src/inputs/day_6_example.txt", LANTERNFISH).unwrap();
+        population.simulate_n_days(80);
+        assert_eq!(population.get_total(), 5934);
     }
 
     #[test]
     fn test_second_example()
     {
-        let mut day_counter_slots = get_counters_from_input("src/inputs/day_6_example.txt");
-        simulate_n_days(&mut day_counter_slots, 256);
-        assert_eq!(get_counters_sum(&day_counter_slots), 26984457539);
+        let mut population = Population::new_from_file("src/inputs/day_6_example.txt", LANTERNFISH).unwrap();
+        population.simulate_n_days(256);
+        assert_eq!(population.get_total(), 26984457539);
     }
 
     #[test]
     fn test_get_example_counters()
     {
-        let counters = get_counters_from_input("src/inputs/day_6_example.txt");
+        let population = Population::new_from_file("src/inputs/day_6_example.txt", LANTERNFISH).unwrap();
+        let counters   = population.get_counters();
 
         assert_eq!(counters[1], 1);
         assert_eq!(counters[2], 1);
         assert_eq!(counters[3], 2);
         assert_eq!(counters[4], 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_full_input()
+    {
+        assert_eq!(day_6_1(), Ok(391671));
+        assert_eq!(day_6_2(), Ok(1754000560399));
+        assert!(simulate_input("src/inputs/day_6_missing.txt", LANTERNFISH, 80).is_err());
+    }
+
+    #[test]
+    fn test_newborn_timers_are_valid()
+    {
+        let mut population = Population::new(LANTERNFISH);
+        assert!(population.add(7).is_ok());
+        assert!(population.add(8).is_ok());
+        assert!(population.add(9).is_err());
+
+        // Both reach 0 after 7 and 8 days respectively, giving birth the day after
+        population.simulate_n_days(8);
+        assert_eq!(population.get_total(), 3);
+        population.simulate_n_days(1);
+        assert_eq!(population.get_total(), 4);
+    }
+
+    #[test]
+    fn test_species_variant()
+    {
+        // Gives birth every 3 days, newborns start right away
+        let model = SpeciesModel{ cycle_length: 3, newborn_delay: 0 };
+        let mut population = Population::new(model);
+        population.add(0).unwrap();
+
+        // Every fish doubles the population every 3 days
+        population.simulate_n_days(1);
+        assert_eq!(population.get_counters(), &[0, 0, 2]);
+        population.simulate_n_days(3);
+        assert_eq!(population.get_counters(), &[0, 0, 4]);
+    }
+
+    #[test]
+    fn test_default_model_matches_fixed_slots()
+    {
+        let mut population = Population::new_from_file("src/inputs/day_6_input.txt", LANTERNFISH).unwrap();
+
+        let mut counters = [0; 9];
+        counters.copy_from_slice(population.get_counters());
+        for _ in 0..100
+        {
+            let tmp = counters[0];
+            for i in 0..8
+            {
+                counters[i] = counters[i+1];
+            }
+            counters[6] += tmp;
+            counters[8]  = tmp;
+        }
+
+        population.simulate_n_days(100);
+        assert_eq!(population.get_counters(), &counters);
+    }
+
+    #[test]
+    fn test_fast_simulation_matches_daily_steps()
+    {
+        let mut population = Population::new_from_file("src/inputs/day_6_input.txt", LANTERNFISH).unwrap();
+        let fast_population = Population::new_from_file("src/inputs/day_6_input.txt", LANTERNFISH).unwrap();
+
+        for day in 0..=300
+        {
+            assert_eq!(fast_population.get_total_after(day).to_u128(), Some(population.get_total() as u128));
+            population.simulate_n_days(1);
+        }
+        assert_eq!(day_6_after_n_days(256).map(|total| total.to_string()), Ok("1754000560399".to_string()));
+    }
+
+    #[test]
+    fn test_fast_simulation_variants()
+    {
+        let model = SpeciesModel{ cycle_length: 3, newborn_delay: 0 };
+        let mut population = Population::new(model);
+        population.add(0).unwrap();
+
+        // Doubles every 3 days, starting on the first one
+        assert_eq!(population.get_total_after(1).to_u128(),   Some(2));
+        assert_eq!(population.get_total_after(379).to_u128(), Some(1 << 127));
+        assert_eq!(population.get_total_after(400).to_u128(), None);
+        assert_eq!(population.get_total_after(400), &BigUint::from(1 << 67) * &BigUint::from(1 << 67));
+
+        let mut population = Population::new(SpeciesModel{ cycle_length: 1, newborn_delay: 0 });
+        population.add(0).unwrap();
+        assert_eq!(population.get_total_after(127).to_u128(), Some(1 << 127));
+    }
+
+    #[test]
+    fn test_fast_simulation_beyond_u128()
+    {
+        let population = Population::new_from_file("src/inputs/day_6_input.txt", LANTERNFISH).unwrap();
+
+        // Same simulation, modulo a prime
+        let modulo = 1_000_000_007;
+        let mut counters: Vec<u64> = population.get_counters().iter().map(|&c| c as u64).collect();
+        let mut day = 0;
+        for num_days in [1_000, 20_000]
+        {
+            while day < num_days
+            {
+                day += 1;
+                let parents = counters[0];
+                counters.rotate_left(1);
+                counters[6] = (counters[6] + parents) % modulo;
+            }
+            let expected = counters.iter().sum::<u64>() % modulo;
+
+            let total = population.get_total_after(num_days);
+            assert!(total.to_u128().is_none());
+            assert_eq!(total.rem_u32(modulo as u32) as u64, expected);
+        }
+    }
+
+    #[test]
+    fn test_example_history()
+    {
+        let mut population = Population::new_from_file("src/inputs/day_6_example.txt", LANTERNFISH).unwrap();
+        population.record_history();
+        population.simulate_n_days(80);
+
+        let history = population.get_history();
+        assert_eq!(history.len(), 81);
+        assert_eq!(history[0].total,  5);
+        assert_eq!(history[18].total, 26);
+        assert_eq!(history[80].total, 5934);
+
+        // "After 18 days: 6,0,6,4,5,6,0,1,1,2,6,0,1,1,1,2,2,3,3,4,6,7,8,8,8,8"
+        assert_eq!(history[18].day, 18);
+        assert_eq!(history[18].counters, vec![3, 5, 3, 2, 2, 1, 5, 1, 4]);
+        assert_eq!(population.get_day(), 80);
+    }
+
+    #[test]
+    fn test_history_is_optional()
+    {
+        let mut population = Population::new_from_file("src/inputs/day_6_example.txt", LANTERNFISH).unwrap();
+        population.simulate_n_days(3);
+        assert!(population.get_history().is_empty());
+
+        // Recording starts wherever the simulation is
+        population.record_history();
+        population.simulate_n_days(2);
+        let days: Vec<usize> = population.get_history().iter().map(|record| record.day).collect();
+        assert_eq!(days, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_history_csv()
+    {
+        let mut population = Population::new(SpeciesModel{ cycle_length: 2, newborn_delay: 1 });
+        population.add(0).unwrap();
+        population.record_history();
+        population.simulate_n_days(2);
+
+        let expected = "day,timer_0,timer_1,timer_2,total\n\
+                        0,1,0,0,1\n\
+                        1,0,1,1,2\n\
+                        2,1,1,0,2\n";
+        assert_eq!(population.history_to_csv(), expected);
+    }
+
+    #[test]
+    fn test_invalid_input()
+    {
+        assert!(Population::new_from_file("src/inputs/day_6_example.txt", SpeciesModel{ cycle_length: 3, newborn_delay: 1 }).is_err());
+        assert!(Population::new_from_file("src/inputs/missing.txt", LANTERNFISH).is_err());
+    }
+}
@@ -1,127 +1,240 @@
 use crate::input_parser::parse_input_at;
 
-pub fn day_7_1() -> usize
+pub fn day_7_1() -> u64
 {
-    let mut initial_positions = parse_positions_from_input("src/inputs/day_7_input.txt");
-    let final_position = calculate_optimal_position(&mut initial_positions);
+    let initial_positions = parse_positions_from_input("src/inputs/day_7_input.txt");
+    return find_optimal_alignment(&initial_positions, CostModel::Linear).1;
+}
 
-    return calculate_total_fuel_consumption(&initial_positions, final_position);
+pub fn day_7_2() -> u64
+{
+    let initial_positions = parse_positions_from_input("src/inputs/day_7_input.txt");
+    return find_optimal_alignment(&initial_positions, CostModel::Triangular).1;
 }
 
-pub fn day_7_2() -> usize
+// Prints the fuel needed to align on every position, for both cost models
+pub fn day_7_curves(input_path: &str, width: usize, height: usize)
 {
-    let mut initial_positions = parse_positions_from_input("src/inputs/day_7_input.txt");
-    let final_position = calculate_optimal_position_2(&mut initial_positions);
+    let initial_positions = parse_positions_from_input(input_path);
 
-    return calculate_total_fuel_consumption_2(&initial_positions, final_position);
+    println!("{}", fuel_curves_to_csv(&initial_positions));
+    for (name, model) in [("Linear", CostModel::Linear), ("Triangular", CostModel::Triangular)]
+    {
+        println!("{} cost:", name);
+        print!("{}", render_fuel_curve(&initial_positions, model, width, height));
+    }
 }
 
-fn parse_positions_from_input(input_path: &str) -> Vec<i32>
+fn parse_positions_from_input(input_path: &str) -> Vec<i64>
 {
     let input = parse_input_at(input_path)
                     .unwrap_or("".to_string());
 
     let mut positions = Vec::new();
-    for entry in input.split(",")
+    for entry in input.trim().split(",")
     {
-        positions.push( entry.parse::<i32>().unwrap_or(0) );
+        positions.push( entry.trim().parse::<i64>().unwrap_or(0) );
     }
     return positions;
 }
 
-// Basically a median
-fn calculate_optimal_position(initial_positions: &mut Vec<i32>) -> i32
+#[derive(Clone, Copy)]
+enum CostModel
 {
-    initial_positions.sort_unstable();
+    // Every step costs 1
+    Linear,
+    // The Nth step costs N
+    Triangular,
+    // The square of the distance
+    Quadratic,
+    // Fuel needed for a given distance. Must be non-decreasing and convex,
+    // or the solver may miss the optimum.
+    Custom(fn(u64) -> u64)
+}
 
-    if initial_positions.len() % 2 == 0
+// Fuel saturates at u64::MAX instead of overflowing, totals included
+impl CostModel
+{
+    pub fn get_fuel(&self, distance: u64) -> u64
     {
-        let i = ( initial_positions.len() as f32 * 0.5 ).floor() as usize;
-        let j = ( initial_positions.len() as f32 * 0.5 ).ceil()  as usize;
+        return match self
+        {
+            CostModel::Linear     => distance,
+            // Halve whichever factor is even first, so only the product can overflow
+            CostModel::Triangular => if distance.is_multiple_of(2) { (distance / 2).saturating_mul(distance + 1) }
+                                     else                            { distance.saturating_mul(distance / 2 + 1) },
+            CostModel::Quadratic  => distance.saturating_mul(distance),
+            CostModel::Custom(f)  => f(distance)
+        };
+    }
+}
 
-        let a = initial_positions[i];
-        let b = initial_positions[j];
+fn calculate_total_fuel_consumption(initial_positions: &[i64], end_position: i64, model: CostModel) -> u64
+{
+    return initial_positions.iter()
+                            .map(|pos| model.get_fuel(pos.abs_diff(end_position)))
+                            .fold(0, u64::saturating_add);
+}
 
-        return (a+b) / 2;
-    }
+// Returns (position, total fuel)
+fn find_optimal_alignment(initial_positions: &[i64], model: CostModel) -> (i64, u64)
+{
+    if initial_positions.is_empty() { return (0, 0); }
 
-    let median_idx = initial_positions.len() / 2;
-    return initial_positions[median_idx];
+    let position = match model
+    {
+        CostModel::Linear     => get_median(initial_positions),
+        CostModel::Triangular => get_triangular_optimum(initial_positions),
+        _                     => return search_optimal_alignment(initial_positions, model)
+    };
+    return (position, calculate_total_fuel_consumption(initial_positions, position, model));
 }
 
-fn calculate_optimal_position_2(initial_positions: &mut Vec<i32>) -> i32
+// Moving one step right changes the linear cost by (crabs on the left or
+// below) - (crabs on the right), so any median is optimal
+fn get_median(initial_positions: &[i64]) -> i64
 {
-    if initial_positions.is_empty() { return 0; }
+    let mut sorted = initial_positions.to_vec();
+    let median_idx = (sorted.len() - 1) / 2;
+    return *sorted.select_nth_unstable(median_idx).1;
+}
 
-    initial_positions.sort_unstable();
+// Moving one step right from X changes the triangular cost by
+// N * (X - mean) + (crabs at or below X), which is never negative from
+// ceil(mean) on and always negative up to floor(mean) - 1
+fn get_triangular_optimum(initial_positions: &[i64]) -> i64
+{
+    let sum = initial_positions.iter().map(|&pos| pos as i128).sum::<i128>();
+    let num = initial_positions.len() as i128;
 
-    let mut current_min = usize::MAX;
-    let max_pos = initial_positions.last()
-                                   .unwrap();
+    let floor = sum.div_euclid(num) as i64;
+    let ceil  = floor + if sum.rem_euclid(num) == 0 {0} else {1};
 
-    for pos in 0..*max_pos
-    {
-        let candidate = calculate_total_fuel_consumption_2(initial_positions, pos);
+    let floor_fuel = calculate_total_fuel_consumption(initial_positions, floor, CostModel::Triangular);
+    let ceil_fuel  = calculate_total_fuel_consumption(initial_positions, ceil,  CostModel::Triangular);
+    return if ceil_fuel < floor_fuel {ceil} else {floor};
+}
 
-        if candidate > current_min { return pos - 1; }
+// For any other cost.
+// The total fuel is convex on the end position, as a sum of convex costs, so
+// the optimum is the first position from which moving right doesn't pay off.
+fn search_optimal_alignment(initial_positions: &[i64], model: CostModel) -> (i64, u64)
+{
+    let (mut low, mut high) = match (initial_positions.iter().min(), initial_positions.iter().max())
+    {
+        (Some(&min), Some(&max)) => (min, max),
+        _                        => return (0, 0)
+    };
 
-        current_min = candidate;
+    // Binary search over the sign of the slope
+    while low < high
+    {
+        let mid = low + (high - low) / 2;
+
+        let here  = calculate_total_fuel_consumption(initial_positions, mid,     model);
+        let right = calculate_total_fuel_consumption(initial_positions, mid + 1, model);
+        if right >= here
+        {
+            high = mid;
+        }
+        else
+        {
+            low = mid + 1;
+        }
     }
 
-    return *max_pos;
+    return (low, calculate_total_fuel_consumption(initial_positions, low, model));
 }
 
-fn calculate_total_fuel_consumption(initial_positions: &Vec<i32>, end_position: i32) -> usize
+// (position, total fuel) for every position between the leftmost and the rightmost crab
+fn get_fuel_curve(initial_positions: &[i64], model: CostModel) -> Vec<(i64, u64)>
 {
-    let mut total_fuel_consumption = 0;
-    for pos in initial_positions
+    let (min, max) = match (initial_positions.iter().min(), initial_positions.iter().max())
     {
-        if *pos == end_position { continue; }
-
-        total_fuel_consumption += (end_position - pos).abs();
-    }
+        (Some(&min), Some(&max)) => (min, max),
+        _                        => return Vec::new()
+    };
 
-    return total_fuel_consumption as usize;
+    return (min..=max).map(|pos| (pos, calculate_total_fuel_consumption(initial_positions, pos, model)))
+                      .collect();
 }
 
-fn calculate_total_fuel_consumption_2(initial_positions: &Vec<i32>, end_position: i32) -> usize
+fn fuel_curves_to_csv(initial_positions: &[i64]) -> String
 {
-    let mut total_fuel_consumption: u32 = 0;
-    for pos in initial_positions
+    let linear     = get_fuel_curve(initial_positions, CostModel::Linear);
+    let triangular = get_fuel_curve(initial_positions, CostModel::Triangular);
+
+    let mut csv = "position,linear,triangular\n".to_string();
+    for ((pos, linear_fuel), (_, triangular_fuel)) in linear.iter().zip(triangular.iter())
     {
-        if *pos == end_position { continue; }
+        csv += &format!("{},{},{}\n", pos, linear_fuel, triangular_fuel);
+    }
+    return csv;
+}
+
+// Plots the curve with one column per group of positions (showing the
+// cheapest one in the group) and the lowest fuel on the bottom row.
+// The optimal position is marked with 'O', the rest with '*'.
+fn render_fuel_curve(initial_positions: &[i64], model: CostModel, width: usize, height: usize) -> String
+{
+    let curve = get_fuel_curve(initial_positions, model);
+    if curve.is_empty() || width == 0 || height == 0 { return String::new(); }
+
+    let (optimal_position, _) = find_optimal_alignment(initial_positions, model);
 
-        let distance = (end_position - pos).abs() as u32;
-        let fuel_needed: u32 = (0..distance+1).sum();
+    let width    = width.min(curve.len());
+    let min_fuel = curve.iter().map(|&(_, fuel)| fuel).min().unwrap();
+    let max_fuel = curve.iter().map(|&(_, fuel)| fuel).max().unwrap();
 
-        total_fuel_consumption += fuel_needed;
+    // (row, is optimal) for each column, row 0 being the bottom one
+    let mut columns = Vec::with_capacity(width);
+    for c in 0..width
+    {
+        let group = &curve[c * curve.len() / width .. (c+1) * curve.len() / width];
+        let fuel  = group.iter().map(|&(_, fuel)| fuel).min().unwrap();
+
+        let row = if max_fuel == min_fuel { 0 }
+                  else { ((fuel - min_fuel) as u128 * (height - 1) as u128 / (max_fuel - min_fuel) as u128) as usize };
+        columns.push( (row, group.iter().any(|&(pos, _)| pos == optimal_position)) );
     }
 
-    return total_fuel_consumption as usize;
+    let mut result = String::new();
+    for row in (0..height).rev()
+    {
+        let line: String = columns.iter()
+                                  .map(|&(column_row, is_optimal)| match (column_row == row, is_optimal)
+                                  {
+                                      (false, _)    => ' ',
+                                      (true, false) => '*',
+                                      (true, true)  => 'O'
+                                  })
+                                  .collect();
+        result += line.trim_end();
+        result += "\n";
+    }
+    result += &format!("{}..{}: {}..{} fuel\n", curve[0].0, curve[curve.len()-1].0, min_fuel, max_fuel);
+    return result;
 }
 
 #[cfg(test)]
 mod tests
 {
     use super::*;
+    use crate::test_rng::Rng;
 
     #[test]
     fn test_first_example()
     {
-        let mut initial_positions = parse_positions_from_input("src/inputs/day_7_example.txt");
-        let final_position        = calculate_optimal_position(&mut initial_positions);
-        let fuel_consumption      = calculate_total_fuel_consumption(&initial_positions,
-                                                                     final_position);
+        let initial_positions = parse_positions_from_input("src/inputs/day_7_example.txt");
+        let (_, fuel_consumption) = find_optimal_alignment(&initial_positions, CostModel::Linear);
         assert_eq!(fuel_consumption, 37);
     }
 
     #[test]
     fn test_second_example()
     {
-        let mut initial_positions = parse_positions_from_input("src/inputs/day_7_example.txt");
-        let final_position        = calculate_optimal_position_2(&mut initial_positions);
-        let fuel_consumption      = calculate_total_fuel_consumption_2(&initial_positions,
-                                                                       final_position);
+        let initial_positions = parse_positions_from_input("src/inputs/day_7_example.txt");
+        let (_, fuel_consumption) = find_optimal_alignment(&initial_positions, CostModel::Triangular);
         assert_eq!(fuel_consumption, 168);
     }
 
@@ -130,13 +243,14 @@ mod tests
     {
         let positions = parse_positions_from_input("src/inputs/day_7_example.txt");
         assert_eq!(positions.len(), 10);
+        assert_eq!(positions[9], 14);
     }
 
     #[test]
     fn test_calculate_optimal_position()
     {
-        let mut positions = parse_positions_from_input("src/inputs/day_7_example.txt");
-        assert_eq!(calculate_optimal_position(&mut positions), 2);
+        let positions = parse_positions_from_input("src/inputs/day_7_example.txt");
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Linear).0, 2);
     }
 
     #[test ]
@@ -144,17 +258,17 @@ mod tests
     {
         let initial_positions = parse_positions_from_input("src/inputs/day_7_example.txt");
 
-        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 1),  41);
-        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 2),  37);
-        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 3),  39);
-        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 10), 71);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 1,  CostModel::Linear), 41);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 2,  CostModel::Linear), 37);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 3,  CostModel::Linear), 39);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 10, CostModel::Linear), 71);
     }
 
     #[test]
     fn test_calculate_optimal_position_2()
     {
-        let mut positions = parse_positions_from_input("src/inputs/day_7_example.txt");
-        assert_eq!(calculate_optimal_position_2(&mut positions), 5);
+        let positions = parse_positions_from_input("src/inputs/day_7_example.txt");
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Triangular).0, 5);
     }
 
     #[test ]
@@ -162,10 +276,166 @@ mod tests
     {
         let initial_positions = parse_positions_from_input("src/inputs/day_7_example.txt");
 
-        assert_eq!(calculate_total_fuel_consumption_2(&initial_positions, 1), 242);
-        assert_eq!(calculate_total_fuel_consumption_2(&initial_positions, 2), 206);
-        assert_eq!(calculate_total_fuel_consumption_2(&initial_positions, 3), 183);
-        assert_eq!(calculate_total_fuel_consumption_2(&initial_positions, 5), 168);
-        assert_eq!(calculate_total_fuel_consumption_2(&initial_positions, 6), 176);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 1, CostModel::Triangular), 242);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 2, CostModel::Triangular), 206);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 3, CostModel::Triangular), 183);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 5, CostModel::Triangular), 168);
+        assert_eq!(calculate_total_fuel_consumption(&initial_positions, 6, CostModel::Triangular), 176);
+    }
+
+    #[test]
+    fn test_full_input()
+    {
+        assert_eq!(day_7_1(), 343605);
+        assert_eq!(day_7_2(), 96744904);
+
+        let positions = parse_positions_from_input("src/inputs/day_7_input.txt");
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Quadratic).1, 193131735);
+    }
+
+    #[test]
+    fn test_far_apart_crabs()
+    {
+        assert_eq!(CostModel::Triangular.get_fuel(6_000_000_000), 18_000_000_003_000_000_000);
+        assert_eq!(CostModel::Triangular.get_fuel(u64::MAX),      u64::MAX);
+        assert_eq!(CostModel::Quadratic.get_fuel(1 << 32),        u64::MAX);
+
+        let positions = [-3_000_000_000, 3_000_000_000];
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Triangular), (0, 9_000_000_003_000_000_000));
+
+        // Too much fuel for a u64 anywhere, so it saturates
+        let positions = [0, 10_000_000_000];
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Linear),          (0, 10_000_000_000));
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Triangular).1, u64::MAX);
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Quadratic).1,  u64::MAX);
+    }
+
+    #[test]
+    fn test_custom_cost_model()
+    {
+        let positions = parse_positions_from_input("src/inputs/day_7_example.txt");
+
+        // Free up to 3 steps, then 10 per step
+        let model = CostModel::Custom(|distance| distance.saturating_sub(3) * 10);
+        let (position, fuel) = find_optimal_alignment(&positions, model);
+
+        let expected = (0..=16).map(|pos| calculate_total_fuel_consumption(&positions, pos, model))
+                               .min()
+                               .unwrap();
+        assert_eq!(fuel, expected);
+        assert_eq!(calculate_total_fuel_consumption(&positions, position, model), fuel);
+    }
+
+    // Tries every position in range
+    fn find_optimal_alignment_by_brute_force(initial_positions: &[i64], model: CostModel) -> (i64, u64)
+    {
+        let min = *initial_positions.iter().min().unwrap();
+        let max = *initial_positions.iter().max().unwrap();
+
+        return (min..=max).map(|pos| (pos, calculate_total_fuel_consumption(initial_positions, pos, model)))
+                          .min_by_key(|&(_, fuel)| fuel)
+                          .unwrap();
+    }
+
+    #[test]
+    fn test_closed_forms_against_brute_force()
+    {
+        let mut rng = Rng::new(0x2021_0007);
+        for _ in 0..500
+        {
+            let num_crabs = 1 + rng.next(40) as usize;
+            let spread    = 1 + rng.next(300);
+            let offset    = rng.next(200) as i64 - 100;
+
+            let positions: Vec<i64> = (0..num_crabs).map(|_| rng.next(spread) as i64 + offset).collect();
+
+            for model in [CostModel::Linear, CostModel::Triangular]
+            {
+                let (position, fuel) = find_optimal_alignment(&positions, model);
+                assert_eq!(fuel, find_optimal_alignment_by_brute_force(&positions, model).1, "{:?}", positions);
+                assert_eq!(fuel, search_optimal_alignment(&positions, model).1);
+                assert_eq!(fuel, calculate_total_fuel_consumption(&positions, position, model));
+            }
+        }
+    }
+
+    #[test]
+    fn test_even_number_of_crabs()
+    {
+        // Every position between the middle two is optimal
+        let positions = vec![-3, -3, 4, 4];
+        let (position, fuel) = find_optimal_alignment(&positions, CostModel::Linear);
+        assert_eq!(position, -3);
+        assert_eq!(fuel, 14);
+        assert_eq!(calculate_total_fuel_consumption(&positions, 0, CostModel::Linear), 14);
+
+        // Mean is 1.5, only 1 and 2 are worth checking
+        let positions = vec![0, 0, 0, 6];
+        assert_eq!(find_optimal_alignment(&positions, CostModel::Triangular),
+                   find_optimal_alignment_by_brute_force(&positions, CostModel::Triangular));
+    }
+
+    #[test]
+    fn test_fuel_curves_csv()
+    {
+        let csv = fuel_curves_to_csv(&[0, 2]);
+        assert_eq!(csv, "position,linear,triangular\n\
+                         0,2,3\n\
+                         1,2,2\n\
+                         2,2,3\n");
+
+        let positions = parse_positions_from_input("src/inputs/day_7_example.txt");
+        let csv = fuel_curves_to_csv(&positions);
+        assert_eq!(csv.lines().count(), 1 + 17);
+        assert!(csv.contains("\n2,37,206\n"));
+        assert!(csv.contains("\n5,45,168\n"));
+    }
+
+    #[test]
+    fn test_render_fuel_curve()
+    {
+        assert_eq!(render_fuel_curve(&[0, 2], CostModel::Triangular, 3, 2), "* *\n O\n0..2: 2..3 fuel\n");
+
+        // Flat curve, every position is as good as the median
+        assert_eq!(render_fuel_curve(&[0, 2], CostModel::Linear, 3, 2), "\nO**\n0..2: 2..2 fuel\n");
+    }
+
+    #[test]
+    fn test_render_example_fuel_curve()
+    {
+        let positions = parse_positions_from_input("src/inputs/day_7_example.txt");
+
+        let plot = render_fuel_curve(&positions, CostModel::Triangular, 17, 10);
+        let rows: Vec<&str> = plot.lines().collect();
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[9], "  ***O***");
+        assert_eq!(rows[10], "0..16: 168..817 fuel");
+
+        // One mark per column
+        for c in 0..17
+        {
+            let marks = rows[..10].iter().filter(|row| matches!(row.chars().nth(c), Some('*') | Some('O'))).count();
+            assert_eq!(marks, 1);
+        }
+
+        // Narrower than the range, the optimum is still there
+        let plot = render_fuel_curve(&positions, CostModel::Linear, 5, 4);
+        assert_eq!(plot.lines().count(), 5);
+        assert_eq!(plot.matches('O').count(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_far_away_crabs()
+    {
+        // Would overflow 32 bits with the triangular cost
+        let positions = vec![0, 1_000_000, 1_000_000, 3_000_000];
+
+        let (position, fuel) = find_optimal_alignment(&positions, CostModel::Triangular);
+        assert_eq!(position, 1_250_000);
+        assert_eq!(fuel, CostModel::Triangular.get_fuel(1_250_000)
+                         + 2 * CostModel::Triangular.get_fuel(250_000)
+                         +     CostModel::Triangular.get_fuel(1_750_000));
+
+        assert_eq!(find_optimal_alignment(&[], CostModel::Linear), (0, 0));
+    }
+}
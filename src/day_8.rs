@@ -1,16 +1,153 @@
-use crate::input_parser::parse_input_at;
+use crate::
+{
+    input_parser::parse_input_at,
+    segment_display::{DisplayDefinition, DecodeError, SegmentMask}
+};
+
+// Every display has this many digits
+const NUM_OUTPUTS: usize = 4;
 
-pub fn day_8_1(input_path: &str) -> usize
+// Fails on the first invalid entry, the validation report lists all of them
+pub fn day_8_1(input_path: &str) -> Result<usize, String>
 {
-    let input = parse_input_at(input_path).unwrap_or_default();
+    let input   = parse_input_at(input_path).map_err(|why| why.to_string())?;
+    let display = DisplayDefinition::seven_segment();
 
     let mut count = 0;
-    for entry in input.lines()
+    for (entry, _) in get_valid_entries(&input, &display)?
     {
         let out_blocks = get_output_blocks_from_entry(entry);
-        count += count_numbers_with_unique_patterns(out_blocks);
+        count += count_numbers_with_unique_patterns(out_blocks, &display);
     }
-    return count;
+    return Ok(count);
+}
+
+pub fn day_8_2(input_path: &str) -> Result<usize, String>
+{
+    let input   = parse_input_at(input_path).map_err(|why| why.to_string())?;
+    let display = DisplayDefinition::seven_segment();
+
+    let entries = get_valid_entries(&input, &display)?;
+    return Ok( entries.iter().map(|&(_, value)| value).sum() );
+}
+
+pub fn print_validation_report(input_path: &str)
+{
+    let input = parse_input_at(input_path).unwrap_or_default();
+    print!("{}", render_validation_report(&input, &DisplayDefinition::seven_segment()));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EntryIssue
+{
+    MissingSeparator,
+    WrongPatternCount(usize),
+    WrongOutputCount(usize),
+    UnknownLetter(String, char),
+    RepeatedLetter(String, char),
+    // Same letters as an earlier pattern, maybe in another order
+    DuplicatedPattern(String),
+    // Output whose letters don't match any of the patterns
+    UnknownOutput(String),
+    // Well formed, but no single wiring explains it
+    Undecodable(DecodeError)
+}
+
+// The decoded value of a valid entry, otherwise every problem found in it
+fn validate_entry(entry: &str, display: &DisplayDefinition) -> Result<usize, Vec<EntryIssue>>
+{
+    let mut issues = Vec::new();
+    if !entry.contains('|') { issues.push(EntryIssue::MissingSeparator); }
+
+    let patterns = get_pattern_blocks_from_entry(entry);
+    let outputs  = get_output_blocks_from_entry(entry);
+
+    if patterns.len() != display.get_num_glyphs() { issues.push(EntryIssue::WrongPatternCount(patterns.len())); }
+    if outputs.len()  != NUM_OUTPUTS             { issues.push(EntryIssue::WrongOutputCount(outputs.len())); }
+
+    for block in patterns.iter().chain(outputs.iter())
+    {
+        for (i, c) in block.chars().enumerate()
+        {
+            if display.parse_pattern(&c.to_string()).is_err()
+            {
+                issues.push(EntryIssue::UnknownLetter(block.to_string(), c));
+            }
+            else if block.chars().take(i).any(|other| other == c)
+            {
+                issues.push(EntryIssue::RepeatedLetter(block.to_string(), c));
+            }
+        }
+    }
+
+    // Letters in any order, so compare as segment masks
+    let pattern_masks: Vec<Option<SegmentMask>> = patterns.iter().map(|block| display.parse_pattern(block).ok()).collect();
+    for i in 0..patterns.len()
+    {
+        if pattern_masks[i].is_some() && pattern_masks[..i].contains(&pattern_masks[i])
+        {
+            issues.push(EntryIssue::DuplicatedPattern(patterns[i].to_string()));
+        }
+    }
+    for output in &outputs
+    {
+        let mask = display.parse_pattern(output).ok();
+        if mask.is_some() && !pattern_masks.contains(&mask)
+        {
+            issues.push(EntryIssue::UnknownOutput(output.to_string()));
+        }
+    }
+
+    // Only worth deducing the wiring of otherwise valid entries
+    if !issues.is_empty()
+    {
+        return Err(issues);
+    }
+    return decode_entry(entry, display).map_err(|why| vec![EntryIssue::Undecodable(why)]);
+}
+
+// One line per entry, blank lines are ignored
+fn render_validation_report(input: &str, display: &DisplayDefinition) -> String
+{
+    let mut report = String::new();
+    for (i, entry) in input.lines().enumerate()
+    {
+        if entry.trim().is_empty() { continue; }
+
+        match validate_entry(entry, display)
+        {
+            Ok(_) => report += &format!("Line {}: OK\n", i+1),
+            Err(issues) =>
+            {
+                let issues: Vec<String> = issues.iter().map(|issue| format!("{:?}", issue)).collect();
+                report += &format!("Line {}: {}\n", i+1, issues.join(", "));
+            }
+        }
+    }
+    return report;
+}
+
+// All the entries along with their decoded value, as long as every one of them is valid
+fn get_valid_entries<'a>(input: &'a str, display: &DisplayDefinition) -> Result<Vec<(&'a str, usize)>, String>
+{
+    let mut entries = Vec::new();
+    for (i, entry) in input.lines().enumerate()
+    {
+        if entry.trim().is_empty() { continue; }
+
+        let value = validate_entry(entry, display).map_err(|issues| format!("Line {}: {:?}", i+1, issues))?;
+        entries.push((entry, value));
+    }
+    return Ok(entries);
+}
+
+fn get_pattern_blocks_from_entry(entry: &str) -> Vec<&str>
+{
+    let (patterns, _) = entry.split_once("|")
+                             .unwrap_or_default();
+
+    return patterns.split_whitespace()
+                   .collect();
 }
 
 fn get_output_blocks_from_entry(entry: &str) -> Vec<&str>
@@ -22,18 +159,22 @@ fn get_output_blocks_from_entry(entry: &str) -> Vec<&str>
                  .collect();
 }
 
-fn count_numbers_with_unique_patterns(blocks: Vec<&str>) -> usize
+// Outputs read as a base 10 number, most significant digit first
+fn decode_entry(entry: &str, display: &DisplayDefinition) -> Result<usize, DecodeError>
 {
-    let mut count = 0;
-    for block in blocks
-    {
-        match block.chars().count()
-        {
-            2|3|4|7 => count += 1,
-            _       => continue
-        }
-    }
-    return count;
+    let digits = display.decode_entry(&get_pattern_blocks_from_entry(entry),
+                                      &get_output_blocks_from_entry(entry))?;
+
+    return Ok( digits.iter().fold(0, |value, digit| value * 10 + digit) );
+}
+
+// Patterns whose size alone tells which glyph they are
+fn count_numbers_with_unique_patterns(blocks: Vec<&str>, display: &DisplayDefinition) -> usize
+{
+    let unique_sizes = display.get_unique_sizes();
+    return blocks.iter()
+                 .filter(|block| unique_sizes.contains(&block.chars().count()))
+                 .count();
 }
 
 #[cfg(test)]
@@ -44,7 +185,7 @@ mod tests
     #[test]
     fn test_first_puzzle()
     {
-        assert_eq!(day_8_1("src/inputs/day_8_example.txt"), 26);
+        assert_eq!(day_8_1("src/inputs/day_8_example.txt"), Ok(26));
     }
 
     #[test]
@@ -52,12 +193,98 @@ mod tests
     {
         let entry = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
         assert_eq!(get_output_blocks_from_entry(entry).len(), 4);
+        assert_eq!(get_pattern_blocks_from_entry(entry).len(), 10);
     }
 
     #[test]
     fn test_count_numbers_with_unique_patterns()
     {
         let output_blocks = vec!["fdgacbe", "cefdb", "cefbgd", "gcbe"];
-        assert_eq!(count_numbers_with_unique_patterns(output_blocks), 2);
+        assert_eq!(count_numbers_with_unique_patterns(output_blocks, &DisplayDefinition::seven_segment()), 2);
+    }
+
+    #[test]
+    fn test_decode_entry()
+    {
+        let entry = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert_eq!(decode_entry(entry, &DisplayDefinition::seven_segment()), Ok(5353));
+    }
+
+    #[test]
+    fn test_second_puzzle()
+    {
+        assert_eq!(day_8_2("src/inputs/day_8_example.txt"), Ok(61229));
+    }
+
+    #[test]
+    fn test_valid_entries()
+    {
+        let display = DisplayDefinition::seven_segment();
+        let input   = parse_input_at("src/inputs/day_8_input.txt").unwrap();
+
+        let report = render_validation_report(&input, &display);
+        assert_eq!(report.lines().count(), 200);
+        assert!(report.lines().all(|line| line.ends_with(": OK")));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_malformed_entries()
+    {
+        let display = DisplayDefinition::seven_segment();
+        let entry   = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+
+        assert_eq!(validate_entry(entry, &display), Err(vec![EntryIssue::MissingSeparator,
+                                                             EntryIssue::WrongPatternCount(0),
+                                                             EntryIssue::WrongOutputCount(0)]));
+
+        let entry = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb | cdfeb fcadb cdfeb";
+        assert_eq!(validate_entry(entry, &display), Err(vec![EntryIssue::WrongPatternCount(9),
+                                                             EntryIssue::WrongOutputCount(3)]));
+
+        let entry = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfxb cddaf";
+        assert_eq!(validate_entry(entry, &display), Err(vec![EntryIssue::UnknownLetter("cdfxb".to_string(), 'x'),
+                                                             EntryIssue::RepeatedLetter("cddaf".to_string(), 'd'),
+                                                             EntryIssue::UnknownOutput("cddaf".to_string())]));
+    }
+
+    #[test]
+    fn test_inconsistent_entries()
+    {
+        let display = DisplayDefinition::seven_segment();
+
+        // "bced" is "cdfbe" shuffled and without f, not among the patterns
+        let entry = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | bcefd fcadb bced cdbaf";
+        assert_eq!(validate_entry(entry, &display), Err(vec![EntryIssue::UnknownOutput("bced".to_string())]));
+
+        let entry = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb bfdce | cdfeb fcadb cdfeb cdbaf";
+        assert_eq!(validate_entry(entry, &display), Err(vec![EntryIssue::DuplicatedPattern("bfdce".to_string())]));
+
+        // Well formed, but two patterns of size 2
+        let entry = "acedgfb cdfbe gcdfa fbcad da cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert_eq!(validate_entry(entry, &display), Err(vec![EntryIssue::Undecodable(DecodeError::Contradictory)]));
+    }
+
+    #[test]
+    fn test_invalid_entries_are_reported()
+    {
+        let display = DisplayDefinition::seven_segment();
+        let input   = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf\n\
+                       acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab cdfeb fcadb cdfeb cdbaf\n\
+                       \n";
+
+        assert_eq!(get_valid_entries(input, &display),
+                   Err("Line 2: [MissingSeparator, WrongPatternCount(0), WrongOutputCount(0)]".to_string()));
+        let first = input.lines().next().unwrap();
+        assert_eq!(get_valid_entries(first, &display), Ok(vec![(first, 5353)]));
+        assert_eq!(render_validation_report(input, &display),
+                   "Line 1: OK\n\
+                    Line 2: MissingSeparator, WrongPatternCount(0), WrongOutputCount(0)\n");
+    }
+
+    #[test]
+    fn test_full_input()
+    {
+        assert_eq!(day_8_1("src/inputs/day_8_input.txt"), Ok(479));
+        assert_eq!(day_8_2("src/inputs/day_8_input.txt"), Ok(1041746));
+    }
+}
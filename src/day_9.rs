@@ -1,19 +1,39 @@
-use crate::height_map::HeightMap;
+use crate::height_map::{HeightMap, LowPoint};
 
 pub fn day_9_1() -> usize
 {
-    let mut map = HeightMap::new_from_file("src/inputs/day_9_input.txt");
+    let map = HeightMap::new_from_file("src/inputs/day_9_input.txt");
     let low_points = map.get_low_points();
 
     return calculate_risk(&low_points);
 }
 
-fn calculate_risk(low_points: &Vec<usize>) -> usize
+pub fn day_9_2() -> usize
+{
+    let map = HeightMap::new_from_file("src/inputs/day_9_input.txt");
+    return multiply_largest_basins(&map, 3);
+}
+
+pub fn print_drainage_map(input_path: &str)
+{
+    HeightMap::new_from_file(input_path).get_drainage_map().print();
+}
+
+// Basins are the areas enclosed by ridges
+fn multiply_largest_basins(map: &HeightMap, count: usize) -> usize
+{
+    let mut sizes = map.get_basin_sizes();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+    return sizes.iter().take(count).product();
+}
+
+fn calculate_risk(low_points: &Vec<LowPoint>) -> usize
 {
     let mut risk = 0;
     for p in low_points
     {
-        risk += p + 1;
+        risk += p.height + 1;
     }
     return risk;
 }
@@ -26,7 +46,7 @@ mod tests
     #[test]
     fn test_first_puzzle()
     {
-        let mut map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
+        let map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
         let low_points = map.get_low_points();
         assert_eq!(calculate_risk(&low_points), 15);
     }
@@ -34,15 +54,31 @@ mod tests
     #[test]
     fn test_first_puzzle_full_input()
     {
-        let mut map = HeightMap::new_from_file("src/inputs/day_9_input.txt");
+        let map = HeightMap::new_from_file("src/inputs/day_9_input.txt");
         let low_points = map.get_low_points();
         assert_eq!(calculate_risk(&low_points), 541);
     }
 
+    #[test]
+    fn test_second_puzzle()
+    {
+        let map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
+        assert_eq!(multiply_largest_basins(&map, 3), 1134);
+    }
+
+    #[test]
+    fn test_second_puzzle_full_input()
+    {
+        assert_eq!(day_9_2(), 847504);
+    }
+
     #[test]
     fn test_calculate_risk()
     {
-        let low_points = vec![1,0,5,5];
+        let low_points = vec![LowPoint{ x: 1, y: 0, height: 1 },
+                              LowPoint{ x: 9, y: 0, height: 0 },
+                              LowPoint{ x: 2, y: 2, height: 5 },
+                              LowPoint{ x: 6, y: 4, height: 5 }];
         assert_eq!(calculate_risk(&low_points), 15);
     }
 }
\ No newline at end of file
@@ -5,18 +5,81 @@ pub struct HeightMap
     // NOTE: ROW MAJOR!
     cells: Vec<MapCell>,
     num_rows: usize,
+    num_cols: usize,
+    topology: Topology
+}
+
+// What lies beyond the edges of the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology
+{
+    // Nothing, edge cells have fewer neighbours
+    Bounded,
+    // The opposite edge
+    Torus,
+    // Endless cells of the given value
+    Infinite(usize)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighbour
+{
+    // Wrapped around on a torus
+    pub x: i64,
+    pub y: i64,
+    pub height: usize,
+    // None if it's outside the map
+    pub idx: Option<usize>
+}
+
+// Left, right, top, bottom
+const DIRECTIONS: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowPoint
+{
+    pub x: usize,
+    pub y: usize,
+    pub height: usize
+}
+
+// Cells this high don't belong to any basin
+pub const RIDGE_HEIGHT: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drainage
+{
+    // Index of the low point it drains to, as returned by `get_low_points`
+    Basin(usize),
+    Ridge,
+    // No lower neighbour, but some neighbour as low
+    Plateau,
+    // Flows down onto a plateau, so it never reaches a low point
+    ToPlateau,
+    // Its water splits between different places, here or further down
+    Divide,
+    // Flows off the edge of an infinite map
+    Outside
+}
+
+// Which low point each cell drains to, following the steepest descent
+pub struct DrainageMap
+{
+    labels: Vec<Drainage>,
+    // The lowest neighbours of each cell, if lower than it. Several on ties.
+    downhill: Vec<Vec<usize>>,
+    low_points: Vec<LowPoint>,
     num_cols: usize
 }
 
 struct MapCell
 {
-    val: usize,
-    is_visited: bool
+    val: usize
 }
 
 impl HeightMap
 {
-    fn new() -> Self { Self{ cells: Vec::new(), num_rows: 0, num_cols: 0} }
+    fn new() -> Self { Self{ cells: Vec::new(), num_rows: 0, num_cols: 0, topology: Topology::Bounded } }
 
     pub fn new_from_file(path: &str) -> Self
     {
@@ -41,9 +104,12 @@ impl HeightMap
 
     fn push(&mut self, val: usize)
     {
-        self.cells.push( MapCell{ val, is_visited: false} );
+        self.cells.push( MapCell{ val } );
     }
 
+    pub fn set_topology(&mut self, topology: Topology) { self.topology = topology; }
+    pub fn get_topology(&self) -> Topology { self.topology }
+
     pub fn get_num_cells(&self)  -> usize          { self.cells.len() }
     pub fn get_cell_value(&self, i: usize) -> usize
     {
@@ -51,102 +117,243 @@ impl HeightMap
         return self.cells[i].val;
     }
 
-    pub fn get_low_points(&mut self) -> Vec<usize>
+    // Cells lower than all their neighbours, in row major order
+    pub fn get_low_points(&self) -> Vec<LowPoint>
     {
         let mut low_points = Vec::new();
-
         for i in 0..self.cells.len()
         {
-            let cell = &mut self.cells[i];
+            let height = self.cells[i].val;
+            if self.get_all_neighbours(i).iter().all(|neighbour| neighbour.height > height)
+            {
+                let y = i / self.num_cols;
+                low_points.push( LowPoint{ x: i - y * self.num_cols, y, height } );
+            }
+        }
+        return low_points;
+    }
 
-            if cell.is_visited { continue; }
+    pub fn get_drainage_map(&self) -> DrainageMap
+    {
+        let low_points = self.get_low_points();
 
-            (*cell).is_visited = true;
+        let mut basin_at = vec![None; self.cells.len()];
+        for (basin, low) in low_points.iter().enumerate()
+        {
+            basin_at[low.y * self.num_cols + low.x] = Some(basin);
+        }
 
-            let low = self.flow_down_from(i);
-            if low.is_some()
+        let mut labels   = vec![Drainage::Ridge; self.cells.len()];
+        let mut downhill = vec![Vec::new(); self.cells.len()];
+
+        // Low points first, so every cell's downhill neighbours are labelled before it
+        let mut order: Vec<usize> = (0..self.cells.len()).collect();
+        order.sort_by_key(|&i| self.cells[i].val);
+
+        for i in order
+        {
+            let height = self.cells[i].val;
+            if height >= RIDGE_HEIGHT { continue; }
+
+            let neighbours = self.get_all_neighbours(i);
+            let lowest     = neighbours.iter().map(|neighbour| neighbour.height).min().unwrap_or(usize::MAX);
+            if lowest >= height
             {
-                low_points.push(low.unwrap());
+                labels[i] = if lowest > height { Drainage::Basin( basin_at[i].unwrap() ) } else { Drainage::Plateau };
+                continue;
             }
+
+            let destinations: Vec<Drainage> = neighbours.iter()
+                                                        .filter(|neighbour| neighbour.height == lowest)
+                                                        .map(|neighbour| match neighbour.idx.map_or(Drainage::Outside, |idx| labels[idx])
+                                                        {
+                                                            Drainage::Plateau => Drainage::ToPlateau,
+                                                            label             => label
+                                                        })
+                                                        .collect();
+            downhill[i] = neighbours.iter()
+                                    .filter(|neighbour| neighbour.height == lowest)
+                                    .filter_map(|neighbour| neighbour.idx)
+                                    .collect();
+
+            let first = destinations[0];
+            labels[i] = if destinations.iter().all(|&label| label == first) { first } else { Drainage::Divide };
         }
 
-        return low_points;
+        return DrainageMap{ labels, downhill, low_points, num_cols: self.num_cols };
     }
 
-    // Simulate a "ball" falling from a given height map position.
-    // The ball will always move to the smaller neighbour, as long as it's
-    // lower than the current point.
-    fn flow_down_from(&mut self, i: usize) -> Option<usize>
+    // Sizes of the areas enclosed by ridges, in order of their first cell.
+    // Unlike the drainage map, every non-ridge cell counts, ties included.
+    pub fn get_basin_sizes(&self) -> Vec<usize>
     {
-        let mut current_value = self.cells[i].val;
-        let (mut cell, mut idx) = self.get_smallest_neighbour_and_idx(i);
-        while cell.val < current_value
+        let mut is_filled = vec![false; self.cells.len()];
+        let mut sizes     = Vec::new();
+        for start in 0..self.cells.len()
         {
-            if cell.is_visited
+            if is_filled[start] || self.cells[start].val >= RIDGE_HEIGHT { continue; }
+
+            is_filled[start] = true;
+            let mut pending  = vec![start];
+            let mut size     = 0;
+            while let Some(i) = pending.pop()
             {
-                // OPTIMISATION
-                // Other cell already went through this, so the resulting low
-                // point is already cached
-                return None;
+                size += 1;
+                for (height, idx) in self.get_neighbours(i)
+                {
+                    if is_filled[idx] || height >= RIDGE_HEIGHT { continue; }
+
+                    is_filled[idx] = true;
+                    pending.push(idx);
+                }
             }
+            sizes.push(size);
+        }
+        return sizes;
+    }
 
-            cell.is_visited = true;
+    // Index of the cell at (x, y), once wrapped around on a torus
+    pub fn get_index_at(&self, x: i64, y: i64) -> Option<usize>
+    {
+        if self.cells.is_empty() { return None; }
 
-            current_value = cell.val;
-            let sn        = self.get_smallest_neighbour_and_idx(idx);
-            cell = sn.0;
-            idx  = sn.1;
-        }
+        let num_cols = self.num_cols as i64;
+        let num_rows = self.num_rows as i64;
+        let (x, y)   = match self.topology
+        {
+            Topology::Torus => (x.rem_euclid(num_cols), y.rem_euclid(num_rows)),
+            _               => (x, y)
+        };
 
-        if cell.val == current_value { return  None; }
-        return Some(current_value);
+        if x < 0 || y < 0 || x >= num_cols || y >= num_rows { return None; }
+        return Some( (y * num_cols + x) as usize );
     }
 
-    fn get_smallest_neighbour_and_idx(&mut self, i: usize) -> (&mut MapCell, usize)
+    // None only outside a bounded map
+    pub fn get_value_at(&self, x: i64, y: i64) -> Option<usize>
     {
-        let mut smaller_neighbours = self.get_neighbours(i);
+        return match (self.get_index_at(x, y), self.topology)
+        {
+            (Some(i), _)                        => Some(self.cells[i].val),
+            (None, Topology::Infinite(default)) => Some(default),
+            (None, _)                           => None
+        };
+    }
+
+    // Left, right, top and bottom, as far as they exist.
+    // On maps 1 or 2 cells wide a torus leads to the same cell from several
+    // directions, or back to (x, y): those are only listed once, or not at all.
+    pub fn get_neighbours_at(&self, x: i64, y: i64) -> Vec<Neighbour>
+    {
+        let own_idx = self.get_index_at(x, y);
 
-        smaller_neighbours.sort_unstable_by(|a, b|{ a.0.cmp(&b.0) });
+        let mut neighbours: Vec<Neighbour> = Vec::with_capacity(4);
+        for (dx, dy) in DIRECTIONS
+        {
+            let height = match self.get_value_at(x + dx, y + dy)
+            {
+                Some(height) => height,
+                None         => continue
+            };
 
-        assert!(!smaller_neighbours.is_empty());
-        // The only case when smaller_neighbours would be empty is when the height map is 1x1
-        let idx = if smaller_neighbours.is_empty() { i } else { smaller_neighbours[0].1 };
-        return (&mut self.cells[idx], idx);
+            let idx = self.get_index_at(x + dx, y + dy);
+            let neighbour = match idx
+            {
+                Some(i) =>
+                {
+                    if idx == own_idx || neighbours.iter().any(|n| n.idx == idx) { continue; }
+                    Neighbour{ x: (i % self.num_cols) as i64, y: (i / self.num_cols) as i64, height, idx }
+                },
+                None => Neighbour{ x: x + dx, y: y + dy, height, idx }
+            };
+            neighbours.push(neighbour);
+        }
+        return neighbours;
     }
 
-    pub fn get_neighbours(&self, i: usize) -> Vec<(usize, usize)>
+    fn get_all_neighbours(&self, i: usize) -> Vec<Neighbour>
     {
         let y = i / self.num_cols;
-        let x = i - y * self.num_cols;
+        return self.get_neighbours_at((i - y * self.num_cols) as i64, y as i64);
+    }
 
-        let left_idx    = i.checked_sub(1);
-        let right_idx   = if i < self.cells.len() - 1 { Some(i + 1) } else { None };
-        let top_idx     = i.checked_sub(self.num_cols);
-        let bottom_idx  = if i < self.cells.len() - self.num_cols { Some(i + self.num_cols) } else { None };
+    // (value, index) of the neighbours inside the map
+    pub fn get_neighbours(&self, i: usize) -> Vec<(usize, usize)>
+    {
+        return self.get_all_neighbours(i)
+                   .iter()
+                   .filter_map(|neighbour| neighbour.idx.map(|idx| (neighbour.height, idx)))
+                   .collect();
+    }
+}
 
-        let mut smaller_neighbours = Vec::with_capacity(4);
-        if x > 0 && left_idx.is_some()
-        {
-            let idx = left_idx.unwrap();
-            smaller_neighbours.push( (self.cells[idx].val, idx) );
-        }
-        if x < self.num_cols && right_idx.is_some()
+impl DrainageMap
+{
+    pub fn get_low_points(&self) -> &[LowPoint] { &self.low_points }
+    pub fn get_drainage(&self, i: usize) -> Drainage { self.labels[i] }
+
+    // Edges of the drainage graph, from a cell to where it flows
+    pub fn get_downhill(&self, i: usize) -> &[usize] { &self.downhill[i] }
+
+    // Cells a ball dropped on `i` rolls through, `i` included.
+    // On ties it takes the first lowest neighbour.
+    pub fn get_flow_path(&self, i: usize) -> Vec<usize>
+    {
+        let mut path = vec![i];
+        while let Some(&next) = self.downhill[*path.last().unwrap()].first()
         {
-            let idx = right_idx.unwrap();
-            smaller_neighbours.push( (self.cells[idx].val, idx) );
+            path.push(next);
         }
-        if y > 0 && top_idx.is_some()
+        return path;
+    }
+
+    // Every cell draining to the low point, in row major order
+    pub fn get_catchment(&self, basin: usize) -> Vec<usize>
+    {
+        return (0..self.labels.len()).filter(|&i| self.labels[i] == Drainage::Basin(basin))
+                                     .collect();
+    }
+
+    pub fn get_basin_sizes(&self) -> Vec<usize>
+    {
+        let mut sizes = vec![0; self.low_points.len()];
+        for label in &self.labels
         {
-            let idx = top_idx.unwrap();
-            smaller_neighbours.push( (self.cells[idx].val, idx) );
+            if let Drainage::Basin(basin) = label { sizes[*basin] += 1; }
         }
-        if y < self.num_rows && bottom_idx.is_some()
+        return sizes;
+    }
+
+    // Basins as letters (cycling after 26), uppercase on their low point.
+    // Ridges are '#', plateaus '.', cells flowing onto them ',', divides '+' and
+    // cells flowing off the map '~'.
+    pub fn render(&self) -> String
+    {
+        let mut result = String::with_capacity(self.labels.len() + self.labels.len() / self.num_cols.max(1));
+        for (i, label) in self.labels.iter().enumerate()
         {
-            let idx = bottom_idx.unwrap();
-            smaller_neighbours.push( (self.cells[idx].val, idx) );
+            result.push(match label
+            {
+                Drainage::Basin(basin) =>
+                {
+                    let low    = self.low_points[*basin];
+                    let letter = (b'a' + (basin % 26) as u8) as char;
+                    if low.y * self.num_cols + low.x == i { letter.to_ascii_uppercase() } else { letter }
+                },
+                Drainage::Ridge     => '#',
+                Drainage::Plateau   => '.',
+                Drainage::ToPlateau => ',',
+                Drainage::Divide    => '+',
+                Drainage::Outside   => '~'
+            });
+            if (i + 1) % self.num_cols.max(1) == 0 { result.push('\n'); }
         }
+        return result;
+    }
 
-        return smaller_neighbours;
+    pub fn print(&self)
+    {
+        print!("{}", self.render());
     }
 }
 
@@ -167,43 +374,322 @@ mod tests
     #[test]
     fn test_get_low_points()
     {
-        let mut map    = HeightMap::new_from_file("src/inputs/day_9_example.txt");
+        let map        = HeightMap::new_from_file("src/inputs/day_9_example.txt");
         let low_points = map.get_low_points();
 
-        println!("{:?}", low_points);
         assert_eq!(low_points.len(), 4);
 
-        assert_eq!(low_points[0], 1);
-        assert_eq!(low_points[1], 5);
-        assert_eq!(low_points[2], 0);
-        assert_eq!(low_points[3], 5);
+        assert_eq!(low_points[0], LowPoint{ x: 1, y: 0, height: 1 });
+        assert_eq!(low_points[1], LowPoint{ x: 9, y: 0, height: 0 });
+        assert_eq!(low_points[2], LowPoint{ x: 2, y: 2, height: 5 });
+        assert_eq!(low_points[3], LowPoint{ x: 6, y: 4, height: 5 });
+
+        // Nothing changes between queries
+        assert_eq!(map.get_low_points(), low_points);
+    }
+
+    #[test]
+    fn test_plateaus_are_not_low_points()
+    {
+        let map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
+        let mut flat = HeightMap::new();
+        for _ in 0..4
+        {
+            flat.push(3);
+        }
+        flat.num_rows = 2;
+        flat.num_cols = 2;
+        assert!(flat.get_low_points().is_empty());
+
+        // Low points are strictly lower than every neighbour
+        for low in map.get_low_points()
+        {
+            let i = low.y * map.num_cols + low.x;
+            assert!(map.get_neighbours(i).iter().all(|&(height, _)| height > low.height));
+        }
+    }
+
+    fn build_map(rows: &[&str]) -> HeightMap
+    {
+        let mut map = HeightMap::new();
+        for row in rows
+        {
+            for c in row.chars()
+            {
+                map.push( c.to_digit(10).unwrap() as usize );
+            }
+            map.num_cols = row.len();
+            map.num_rows += 1;
+        }
+        return map;
+    }
+
+    #[test]
+    fn test_example_drainage()
+    {
+        let map      = HeightMap::new_from_file("src/inputs/day_9_example.txt");
+        let drainage = map.get_drainage_map();
+
+        let expected = "aA###bbbbB\n\
+                        a#ccc#b#bb\n\
+                        #cCccc#d#b\n\
+                        ccccc#ddd#\n\
+                        #c###dDddd\n";
+        assert_eq!(drainage.render(), expected);
+        assert_eq!(drainage.get_basin_sizes(), vec![3, 9, 14, 9]);
+        assert_eq!(drainage.get_low_points(), map.get_low_points().as_slice());
+
+        assert_eq!(drainage.get_catchment(0), vec![0, 1, 10]);
+        assert_eq!(drainage.get_drainage(2), Drainage::Ridge);
+    }
+
+    #[test]
+    fn test_basin_sizes()
+    {
+        let map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
+        assert_eq!(map.get_basin_sizes(), vec![3, 9, 14, 9]);
+
+        // Plateaus and divides still belong to the area around them
+        let map = build_map(&["15199",
+                              "99999",
+                              "99944"]);
+        assert_eq!(map.get_basin_sizes(), vec![3, 2]);
+
+        let mut map = build_map(&["19",
+                                  "99",
+                                  "19"]);
+        assert_eq!(map.get_basin_sizes(), vec![1, 1]);
+        map.set_topology(Topology::Torus);
+        assert_eq!(map.get_basin_sizes(), vec![2]);
+    }
+
+    #[test]
+    fn test_drainage_graph()
+    {
+        let map      = HeightMap::new_from_file("src/inputs/day_9_example.txt");
+        let drainage = map.get_drainage_map();
+
+        // Steepest descent: 8 (14) -> 7 (13) -> 6 (23) -> 5 (22)
+        assert_eq!(drainage.get_flow_path(14), vec![14, 13, 23, 22]);
+        assert_eq!(drainage.get_downhill(22), &[] as &[usize]);
+        assert!(drainage.get_downhill(2).is_empty()); // Ridge
+
+        // Every cell flows into its own basin
+        for i in 0..map.get_num_cells()
+        {
+            for &next in drainage.get_downhill(i)
+            {
+                assert!(map.get_cell_value(next) < map.get_cell_value(i));
+                assert_eq!(drainage.get_drainage(next), drainage.get_drainage(i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_drainage_ties()
+    {
+        // The 5 has two lowest neighbours, each in its own basin,
+        // and the 4s in the corner are a plateau
+        let map = build_map(&["15199",
+                              "99999",
+                              "99944"]);
+        let drainage = map.get_drainage_map();
+
+        assert_eq!(drainage.render(), "A+B##\n#####\n###..\n");
+        assert_eq!(drainage.get_drainage(1),  Drainage::Divide);
+        assert_eq!(drainage.get_drainage(13), Drainage::Plateau);
+        assert_eq!(drainage.get_downhill(1), &[0, 2]);
+        assert_eq!(drainage.get_basin_sizes(), vec![1, 1]);
+
+        // Both lowest neighbours in the same basin is no divide
+        let map = build_map(&["21",
+                              "10"]);
+        let drainage = map.get_drainage_map();
+        assert_eq!(drainage.render(), "aa\naA\n");
+        assert_eq!(drainage.get_downhill(0), &[1, 2]);
+
+        // Flowing onto a plateau or a divide isn't being one
+        let map = build_map(&["2114"]);
+        let drainage = map.get_drainage_map();
+        assert_eq!(drainage.render(), ",..,\n");
+        assert_eq!(drainage.get_drainage(0), Drainage::ToPlateau);
+        assert_eq!(drainage.get_drainage(1), Drainage::Plateau);
+
+        let map = build_map(&["1315",
+                              "9499"]);
+        let drainage = map.get_drainage_map();
+        assert_eq!(drainage.render(), "A+Bb\n#+##\n");
+        assert_eq!(drainage.get_drainage(5), Drainage::Divide);
+    }
+
+    // Cell values are their own index, to tell them apart
+    fn build_indexed_map(num_cols: usize, num_rows: usize, topology: Topology) -> HeightMap
+    {
+        let mut map = HeightMap::new();
+        for i in 0..num_cols * num_rows
+        {
+            map.push(i);
+        }
+        map.num_cols = num_cols;
+        map.num_rows = num_rows;
+        map.set_topology(topology);
+        return map;
+    }
+
+    fn get_neighbour_coords(map: &HeightMap, x: i64, y: i64) -> Vec<(i64, i64)>
+    {
+        return map.get_neighbours_at(x, y).iter().map(|n| (n.x, n.y)).collect();
     }
 
     #[test]
-    fn test_trickle_down_from_neighbour()
+    fn test_bounded_corners_and_edges()
     {
-        let mut map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
-        let low = map.flow_down_from(0).unwrap();
+        let map = build_indexed_map(3, 3, Topology::Bounded);
+
+        // Corners
+        assert_eq!(get_neighbour_coords(&map, 0, 0), vec![(1, 0), (0, 1)]);
+        assert_eq!(get_neighbour_coords(&map, 2, 0), vec![(1, 0), (2, 1)]);
+        assert_eq!(get_neighbour_coords(&map, 0, 2), vec![(1, 2), (0, 1)]);
+        assert_eq!(get_neighbour_coords(&map, 2, 2), vec![(1, 2), (2, 1)]);
+        // Edges
+        assert_eq!(get_neighbour_coords(&map, 1, 0), vec![(0, 0), (2, 0), (1, 1)]);
+        assert_eq!(get_neighbour_coords(&map, 0, 1), vec![(1, 1), (0, 0), (0, 2)]);
+        assert_eq!(get_neighbour_coords(&map, 2, 1), vec![(1, 1), (2, 0), (2, 2)]);
+        assert_eq!(get_neighbour_coords(&map, 1, 2), vec![(0, 2), (2, 2), (1, 1)]);
+        // Centre
+        assert_eq!(get_neighbour_coords(&map, 1, 1), vec![(0, 1), (2, 1), (1, 0), (1, 2)]);
+
+        // The right edge doesn't leak into the next row
+        assert_eq!(map.get_neighbours(2), vec![(1, 1), (5, 5)]);
+        assert_eq!(map.get_neighbours(3), vec![(4, 4), (0, 0), (6, 6)]);
+
+        assert_eq!(map.get_neighbours_at(-1, 0).len(), 1); // Only (0, 0)
+        assert_eq!(map.get_value_at(3, 0), None);
+    }
 
-        assert_eq!(low, 1);
+    #[test]
+    fn test_torus_corners_and_edges()
+    {
+        let map = build_indexed_map(3, 3, Topology::Torus);
+
+        assert_eq!(get_neighbour_coords(&map, 0, 0), vec![(2, 0), (1, 0), (0, 2), (0, 1)]);
+        assert_eq!(get_neighbour_coords(&map, 2, 0), vec![(1, 0), (0, 0), (2, 2), (2, 1)]);
+        assert_eq!(get_neighbour_coords(&map, 0, 2), vec![(2, 2), (1, 2), (0, 1), (0, 0)]);
+        assert_eq!(get_neighbour_coords(&map, 2, 2), vec![(1, 2), (0, 2), (2, 1), (2, 0)]);
+        assert_eq!(get_neighbour_coords(&map, 1, 0), vec![(0, 0), (2, 0), (1, 2), (1, 1)]);
+        assert_eq!(get_neighbour_coords(&map, 1, 1), vec![(0, 1), (2, 1), (1, 0), (1, 2)]);
+
+        // Coordinates outside wrap around too
+        assert_eq!(map.get_index_at(-1, -1), Some(8));
+        assert_eq!(map.get_index_at(7, 3),   Some(1));
+        assert_eq!(map.get_value_at(-4, 0),  Some(2));
+
+        // Left and right are the same cell on 2 columns, and the cell itself on 1
+        let narrow = build_indexed_map(2, 3, Topology::Torus);
+        assert_eq!(get_neighbour_coords(&narrow, 0, 0), vec![(1, 0), (0, 2), (0, 1)]);
+        let single = build_indexed_map(1, 1, Topology::Torus);
+        assert!(single.get_neighbours_at(0, 0).is_empty());
     }
 
     #[test]
-    fn test_trickle_down()
+    fn test_infinite_corners_and_edges()
     {
-        let mut map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
-        let low = map.flow_down_from(14).unwrap();
+        let map = build_indexed_map(3, 3, Topology::Infinite(42));
 
-        assert_eq!(low, 5);
+        let corner = map.get_neighbours_at(0, 0);
+        assert_eq!(corner, vec![Neighbour{ x: -1, y:  0, height: 42, idx: None },
+                                Neighbour{ x:  1, y:  0, height: 1,  idx: Some(1) },
+                                Neighbour{ x:  0, y: -1, height: 42, idx: None },
+                                Neighbour{ x:  0, y:  1, height: 3,  idx: Some(3) }]);
+
+        let edge = map.get_neighbours_at(2, 1);
+        assert_eq!(edge.iter().map(|n| n.height).collect::<Vec<usize>>(), vec![4, 42, 2, 8]);
+
+        // Far away, everything is the default
+        assert!(map.get_neighbours_at(100, -100).iter().all(|n| n.height == 42 && n.idx.is_none()));
+
+        // Only cells inside the map have an index
+        assert_eq!(map.get_neighbours(0), vec![(1, 1), (3, 3)]);
+    }
+
+    // Straightforward reference for every topology
+    fn get_expected_neighbours(num_cols: i64, num_rows: i64, x: i64, y: i64, topology: Topology) -> Vec<(i64, i64, usize)>
+    {
+        let mut expected: Vec<(i64, i64, usize)> = Vec::new();
+        for (nx, ny) in [(x-1, y), (x+1, y), (x, y-1), (x, y+1)]
+        {
+            let inside = nx >= 0 && ny >= 0 && nx < num_cols && ny < num_rows;
+            let entry = match topology
+            {
+                Topology::Bounded if !inside => continue,
+                Topology::Infinite(default) if !inside => (nx, ny, default),
+                Topology::Torus =>
+                {
+                    let (wx, wy) = (nx.rem_euclid(num_cols), ny.rem_euclid(num_rows));
+                    if (wx, wy) == (x, y) { continue; }
+                    (wx, wy, (wy * num_cols + wx) as usize)
+                },
+                _ => (nx, ny, (ny * num_cols + nx) as usize)
+            };
+            if !expected.contains(&entry) { expected.push(entry); }
+        }
+        return expected;
     }
 
     #[test]
-    fn test_get_smaller_neighbour()
+    fn test_every_cell_of_small_maps()
     {
-        let mut map = HeightMap::new_from_file("src/inputs/day_9_example.txt");
-        let smaller_neighbour = map.get_smallest_neighbour_and_idx(24);
+        for topology in [Topology::Bounded, Topology::Torus, Topology::Infinite(99)]
+        {
+            for num_cols in 1..=4
+            {
+                for num_rows in 1..=4
+                {
+                    let map = build_indexed_map(num_cols, num_rows, topology);
+                    for y in 0..num_rows as i64
+                    {
+                        for x in 0..num_cols as i64
+                        {
+                            let neighbours: Vec<(i64, i64, usize)> = map.get_neighbours_at(x, y)
+                                                                        .iter()
+                                                                        .map(|n| (n.x, n.y, n.height))
+                                                                        .collect();
+                            assert_eq!(neighbours,
+                                       get_expected_neighbours(num_cols as i64, num_rows as i64, x, y, topology),
+                                       "{:?} {}x{} at ({}, {})", topology, num_cols, num_rows, x, y);
+                        }
+                    }
+                }
+            }
+        }
+    }
 
-        assert_eq!(smaller_neighbour.0.val, 6); // value
-        assert_eq!(smaller_neighbour.1, 23);     // idx
+    #[test]
+    fn test_low_points_per_topology()
+    {
+        let mut map = build_map(&["129",
+                                  "989",
+                                  "099"]);
+        assert_eq!(map.get_low_points().len(), 2);
+
+        // The 0 is right above the 1, across the edge
+        map.set_topology(Topology::Torus);
+        assert_eq!(map.get_low_points(), vec![LowPoint{ x: 0, y: 2, height: 0 }]);
+        assert_eq!(map.get_drainage_map().render(), "aa#\n#a#\nA##\n");
+
+        // Nothing is lower than the outside
+        map.set_topology(Topology::Infinite(0));
+        assert!(map.get_low_points().is_empty());
+
+        map.set_topology(Topology::Infinite(RIDGE_HEIGHT));
+        assert_eq!(map.get_low_points().len(), 2);
+        assert_eq!(map.get_drainage_map().render(), "Aa#\n#a#\nB##\n");
+
+        // Water on the edges flows off the map...
+        map.set_topology(Topology::Infinite(0));
+        assert_eq!(map.get_drainage_map().render(), "~~#\n#~#\n.##\n");
+        // ...unless the map is as low
+        map.set_topology(Topology::Infinite(1));
+        assert_eq!(map.get_drainage_map().render(), ".+#\n#+#\nA##\n");
     }
-}
\ No newline at end of file
+}
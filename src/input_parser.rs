@@ -30,36 +30,47 @@ pub fn parse_drawn_nums(input: &String) -> Vec<i32>
     return result;
 }
 
-pub fn parse_boards(input: &String) -> Vec<BingoBoard>
+// Boards are blocks of whitespace separated numbers, split by empty lines.
+// Their size is taken from the shape of each block.
+pub fn parse_boards(input: &String) -> Result<Vec<BingoBoard>, String>
 {
     let mut result: Vec<BingoBoard> = Vec::new();
 
-    let mut input_lines = input.lines();
-
-    input_lines.next();
-    while input_lines.next().is_some()
+    let mut block: Vec<Vec<i32>> = Vec::new();
+    for line in input.lines().skip(1).chain(std::iter::once(""))
     {
-        let mut new_board: BingoBoard = BingoBoard::default();
-        for y in 0..5
+        if !line.trim().is_empty()
         {
-            let line = input_lines.nth(0)
-                                  .unwrap_or("")
-                                  .to_string();
-            let mut x = 0;
-            for elem in line.trim().split_whitespace()
-            {
-                let cell_value = elem.parse::<i32>()
-                                     .unwrap_or(0);
+            let row = line.split_whitespace()
+                          .map(|elem| elem.parse::<i32>().unwrap_or(0))
+                          .collect();
+            block.push(row);
+            continue;
+        }
+
+        if block.is_empty() { continue; }
+
+        let num_rows = block.len();
+        let num_cols = block[0].len();
+        if block.iter().any(|row| row.len() != num_cols)
+        {
+            return Err("Ragged bingo board!".to_string());
+        }
 
-                new_board.set_cell_at(x, y, cell_value);
-                x += 1;
+        let mut new_board = BingoBoard::new(num_rows, num_cols);
+        for (y, row) in block.iter().enumerate()
+        {
+            for (x, cell_value) in row.iter().enumerate()
+            {
+                new_board.set_cell_at(x, y, *cell_value);
             }
         }
 
         result.push(new_board);
+        block.clear();
     }
 
-    return result;
+    return Ok(result);
 }
 
 #[cfg(test)]
@@ -114,7 +125,25 @@ mod tests
         assert!(input.is_ok());
         let input = input.unwrap();
 
-        let boards = parse_boards(&input);
+        let boards = parse_boards(&input).unwrap();
         assert_eq!(boards.len(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_board_sizes()
+    {
+        let input = "1,2,3\n\n1 2 3\n4 5 6\n7 8 9\n\n1 2 3 4\n5 6 7 8\n".to_string();
+
+        let boards = parse_boards(&input).unwrap();
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].get_size(), (3, 3));
+        assert_eq!(boards[1].get_size(), (4, 2));
+    }
+
+    #[test]
+    fn test_parse_ragged_board()
+    {
+        let input = "1,2,3\n\n1 2 3\n4 5\n".to_string();
+        assert_eq!(parse_boards(&input).map(|boards| boards.len()), Err("Ragged bingo board!".to_string()));
+    }
+}
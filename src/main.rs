@@ -2,8 +2,13 @@
 
 mod input_parser;
 mod coordinates;
+mod big_uint;
 mod bingo_board;
+mod bingo_game;
 mod height_map;
+mod segment_display;
+#[cfg(test)]
+mod test_rng;
 
 mod day_1;
 mod day_2;
@@ -21,33 +26,41 @@ mod day_13;
 mod day_14;
 mod day_15;
 mod day_17;
+mod day_23;
+mod day_24;
+mod day_25;
 
 fn main() {
     // DAY 1
-    // println!("DAY 1, first puzzle: {}", day_1::day_1_1());
-    // println!("DAY 1, second puzzle: {}", day_1::day_1_2());
+    // println!("DAY 1, first puzzle: {:?}", day_1::day_1_1());
+    // println!("DAY 1, second puzzle: {:?}", day_1::day_1_2());
     // DAY 2
-    // println!("DAY 2, first puzzle: {}", day_2::day_2_1());
-    // println!("DAY 2, second puzzle: {}", day_2::day_2_2());
+    // println!("DAY 2, first puzzle: {:?}", day_2::day_2_1());
+    // println!("DAY 2, second puzzle: {:?}", day_2::day_2_2());
     // DAY 3
-    // println!("DAY 3, first puzzle: {}", day_3::day_3_1());
+    // println!("DAY 3, first puzzle: {:?}", day_3::day_3_1());
     // DAY 4
-    // println!("DAY 4, first puzzle: {}", day_4::day_4_1());
-    // println!("DAY 4, second puzzle: {}", day_4::day_4_2());
+    // println!("DAY 4, first puzzle: {:?}", day_4::day_4_1());
+    // println!("DAY 4, second puzzle: {:?}", day_4::day_4_2());
     // DAY 5
     // println!("DAY 5, first puzzle: {}", day_5::day_5_1());
+    // println!("DAY 5, second puzzle: {}", day_5::day_5_2());
     // DAY 6
-    // println!("DAY 6, first puzzle: {}", day_6::day_6_1());
-    // println!("DAY 6, second puzzle: {}", day_6::day_6_2());
+    // println!("DAY 6, first puzzle: {:?}", day_6::day_6_1());
+    // println!("DAY 6, second puzzle: {:?}", day_6::day_6_2());
+    // println!("DAY 6, after 10^6 days: {:?}", day_6::day_6_after_n_days(1_000_000));
     // DAY 7
     // println!("DAY 7, first puzzle: {}", day_7::day_7_1());
     // println!("DAY 7, second puzzle: {}", day_7::day_7_2());
+    // day_7::day_7_curves("src/inputs/day_7_input.txt", 80, 20);
     // DAY 8
-    // println!("DAY 8, first puzzle: {}", day_8::day_8_1("src/inputs/day_8_input.txt"));
-    // println!("DAY 8, second puzzle: {}", day_8::day_8_2("src/inputs/day_8_input.txt"));
+    // println!("DAY 8, first puzzle: {:?}", day_8::day_8_1("src/inputs/day_8_input.txt"));
+    // println!("DAY 8, second puzzle: {:?}", day_8::day_8_2("src/inputs/day_8_input.txt"));
+    // day_8::print_validation_report("src/inputs/day_8_input.txt");
     // DAY 9
     // println!("DAY 9, first puzzle: {}", day_9::day_9_1());
     // println!("DAY 9, second puzzle: {}", day_9::day_9_2());
+    // day_9::print_drainage_map("src/inputs/day_9_input.txt");
     // DAY 10
     // println!("DAY 10, first puzzle: {}", day_10::day_10_1());
     // println!("DAY 10, second puzzle: {}", day_10::day_10_2());
@@ -65,4 +78,13 @@ fn main() {
     // println!("DAY 15, first puzzle: {}", day_15::day_15_1());
     // DAY 17
     println!("DAY 17, first puzzle: {}", day_17::day_17_1());
+    // DAY 23
+    // println!("DAY 23, first puzzle: {:?}", day_23::day_23_1("src/inputs/day_23_input.txt"));
+    // println!("DAY 23, second puzzle: {:?}", day_23::day_23_2("src/inputs/day_23_input.txt"));
+    // DAY 24
+    // println!("DAY 24, first puzzle: {:?}", day_24::day_24_1("src/inputs/day_24_input.txt"));
+    // println!("DAY 24, second puzzle: {:?}", day_24::day_24_2("src/inputs/day_24_input.txt"));
+    // DAY 25
+    // println!("DAY 25, first puzzle: {:?}", day_25::day_25_1("src/inputs/day_25_input.txt"));
+    // println!("DAY 25, steps until still: {:?}", day_25::day_25_print_steps("src/inputs/day_25_input.txt"));
 }
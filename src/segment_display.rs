@@ -0,0 +1,457 @@
+// Wiring deduction for any segment display: given which segments form each
+// glyph and the patterns lit by a display with scrambled wires, finds every
+// wire to segment assignment that explains all of them.
+
+// Segments and wires are bit indices, up to 32 of them
+pub type SegmentMask = u32;
+
+pub struct DisplayDefinition
+{
+    // Segment names, also used to name the wires
+    segments: Vec<char>,
+    // Index N is the glyph for value N
+    glyphs: Vec<SegmentMask>
+}
+
+// Wire index -> segment index
+pub type Wiring = Vec<usize>;
+
+// A pattern, and the only glyph it may be if set
+type PatternConstraint = (SegmentMask, Option<usize>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError
+{
+    // A pattern uses a wire name the display doesn't have
+    UnknownWire(char),
+    // No wiring explains every pattern
+    Contradictory,
+    // Several wirings explain every pattern, and they read the output
+    // differently. Holds two of those readings.
+    Ambiguous(Vec<Vec<usize>>)
+}
+
+impl DisplayDefinition
+{
+    // Glyphs are given as the names of the segments they light up
+    pub fn new(segments: &str, glyphs: &[&str]) -> Self
+    {
+        let segments: Vec<char> = segments.chars().collect();
+        assert!(segments.len() <= SegmentMask::BITS as usize, "Too many segments!");
+
+        let mut display = Self{ segments, glyphs: Vec::new() };
+        for glyph in glyphs
+        {
+            let mask = display.parse_pattern(glyph).expect("Unknown segment in glyph!");
+            assert!(!display.glyphs.contains(&mask), "Duplicated glyph!");
+            display.glyphs.push(mask);
+        }
+        return display;
+    }
+
+    //  aaaa
+    // b    c
+    // b    c
+    //  dddd
+    // e    f
+    // e    f
+    //  gggg
+    pub fn seven_segment() -> Self
+    {
+        return Self::new("abcdefg", &["abcefg", "cf", "acdeg", "acdfg", "bcdf",
+                                      "abdfg", "abdefg", "acf", "abcdefg", "abcdfg"]);
+    }
+
+    pub fn get_num_segments(&self) -> usize { self.segments.len() }
+    pub fn get_num_glyphs(&self)   -> usize { self.glyphs.len() }
+
+    // Letters can come in any order, repeated letters count once
+    pub fn parse_pattern(&self, pattern: &str) -> Result<SegmentMask, DecodeError>
+    {
+        let mut mask = 0;
+        for c in pattern.chars()
+        {
+            let idx = self.segments.iter()
+                                   .position(|&segment| segment == c)
+                                   .ok_or(DecodeError::UnknownWire(c))?;
+            mask |= 1 << idx;
+        }
+        return Ok(mask);
+    }
+
+    // Sizes that only one glyph has, so patterns of that size need no deduction
+    pub fn get_unique_sizes(&self) -> Vec<usize>
+    {
+        let sizes: Vec<usize> = self.glyphs.iter().map(|glyph| glyph.count_ones() as usize).collect();
+
+        let mut unique: Vec<usize> = sizes.iter()
+                                          .copied()
+                                          .filter(|&size| sizes.iter().filter(|&&other| other == size).count() == 1)
+                                          .collect();
+        unique.sort_unstable();
+        return unique;
+    }
+
+    // Every wiring under which each observed pattern is some glyph.
+    // There can be a huge number of them when the observations say little,
+    // `decode_entry` doesn't need to list them.
+    pub fn find_wirings(&self, observations: &[SegmentMask]) -> Vec<Wiring>
+    {
+        let mut wirings = Vec::new();
+        self.search(&Self::get_constraints(observations), |wiring| { wirings.push(wiring); true });
+        return wirings;
+    }
+
+    // Any wiring under which each pattern is some glyph, or the given one if set
+    fn find_any_wiring(&self, constraints: &[PatternConstraint]) -> Option<Wiring>
+    {
+        let mut found = None;
+        self.search(constraints, |wiring| { found = Some(wiring); false });
+        return found;
+    }
+
+    fn get_constraints(observations: &[SegmentMask]) -> Vec<PatternConstraint>
+    {
+        let mut patterns = observations.to_vec();
+        patterns.sort_unstable();
+        patterns.dedup();
+
+        return patterns.iter().map(|&pattern| (pattern, None)).collect();
+    }
+
+    // Applies `wiring` to a pattern and looks the result up among the glyphs
+    pub fn decode(&self, wiring: &[usize], pattern: SegmentMask) -> Option<usize>
+    {
+        let mut segments = 0;
+        for (wire, &segment) in wiring.iter().enumerate()
+        {
+            if pattern & (1 << wire) != 0
+            {
+                segments |= 1 << segment;
+            }
+        }
+        return self.glyphs.iter().position(|&glyph| glyph == segments);
+    }
+
+    // Deduces the wiring from the observed patterns and reads the outputs
+    // with it. Outputs don't have to be among the observations.
+    pub fn decode_entry(&self, observations: &[&str], outputs: &[&str]) -> Result<Vec<usize>, DecodeError>
+    {
+        let mut patterns = Vec::with_capacity(observations.len() + outputs.len());
+        for pattern in observations.iter().chain(outputs.iter())
+        {
+            patterns.push( self.parse_pattern(pattern)? );
+        }
+        let output_patterns = patterns.split_off(observations.len());
+
+        // Outputs are patterns seen on the display too
+        let mut all_patterns = patterns;
+        all_patterns.extend(&output_patterns);
+        let constraints = Self::get_constraints(&all_patterns);
+
+        let wiring  = self.find_any_wiring(&constraints).ok_or(DecodeError::Contradictory)?;
+        let reading: Vec<usize> = output_patterns.iter()
+                                                 .map(|&pattern| self.decode(&wiring, pattern).unwrap())
+                                                 .collect();
+
+        // Rather than listing every wiring, look for one that reads some
+        // output as a different glyph
+        for (i, &pattern) in output_patterns.iter().enumerate()
+        {
+            for glyph in 0..self.glyphs.len()
+            {
+                if glyph == reading[i] || self.glyphs[glyph].count_ones() != pattern.count_ones() { continue; }
+
+                let mut forced = constraints.clone();
+                for constraint in forced.iter_mut().filter(|constraint| constraint.0 == pattern)
+                {
+                    constraint.1 = Some(glyph);
+                }
+
+                if let Some(other) = self.find_any_wiring(&forced)
+                {
+                    let other_reading = output_patterns.iter()
+                                                       .map(|&pattern| self.decode(&other, pattern).unwrap())
+                                                       .collect();
+                    return Err(DecodeError::Ambiguous(vec![reading, other_reading]));
+                }
+            }
+        }
+        return Ok(reading);
+    }
+
+    // Calls `on_wiring` with every wiring found, until it returns false
+    fn search(&self, constraints: &[PatternConstraint], mut on_wiring: impl FnMut(Wiring) -> bool)
+    {
+        let num_segments = self.segments.len();
+        let all_segments = if num_segments == 32 { SegmentMask::MAX } else { (1 << num_segments) - 1 };
+
+        self.search_from(constraints, vec![all_segments; num_segments], &mut on_wiring);
+    }
+
+    // Constraint propagation, then branching on the most constrained wire.
+    // Returns false once the search has to stop.
+    fn search_from(&self,
+                   constraints: &[PatternConstraint],
+                   mut candidates: Vec<SegmentMask>,
+                   on_wiring: &mut impl FnMut(Wiring) -> bool)
+    -> bool
+    {
+        if !self.propagate(constraints, &mut candidates) { return true; }
+
+        let undecided = (0..candidates.len()).filter(|&wire| candidates[wire].count_ones() > 1)
+                                             .min_by_key(|&wire| candidates[wire].count_ones());
+        let wire = match undecided
+        {
+            Some(wire) => wire,
+            None =>
+            {
+                let wiring: Wiring = candidates.iter().map(|c| c.trailing_zeros() as usize).collect();
+                let is_valid = constraints.iter().all(|&(pattern, forced)| match (self.decode(&wiring, pattern), forced)
+                {
+                    (Some(glyph), Some(forced)) => glyph == forced,
+                    (glyph, None)               => glyph.is_some(),
+                    (None, _)                   => false
+                });
+                return !is_valid || on_wiring(wiring);
+            }
+        };
+
+        let mut options = candidates[wire];
+        while options != 0
+        {
+            let segment = options & options.wrapping_neg();
+            options    &= options - 1;
+
+            let mut guess = candidates.clone();
+            guess[wire]   = segment;
+            if !self.search_from(constraints, guess, on_wiring) { return false; }
+        }
+        return true;
+    }
+
+    // Narrows down the segments each wire can drive until nothing changes.
+    // Returns false if some wire or pattern runs out of options.
+    fn propagate(&self, constraints: &[PatternConstraint], candidates: &mut [SegmentMask]) -> bool
+    {
+        let num_wires = candidates.len();
+        loop
+        {
+            let before = candidates.to_vec();
+
+            for &(pattern, forced) in constraints
+            {
+                // Segments lit by some / every glyph this pattern can still be
+                let mut any   = 0;
+                let mut every = SegmentMask::MAX;
+                let mut found = false;
+                for (idx, &glyph) in self.glyphs.iter().enumerate()
+                {
+                    if forced.is_some_and(|forced| forced != idx) { continue; }
+                    if !self.is_glyph_possible(pattern, glyph, candidates) { continue; }
+                    any   |= glyph;
+                    every &= glyph;
+                    found  = true;
+                }
+                if !found { return false; }
+
+                for (wire, candidate) in candidates.iter_mut().enumerate()
+                {
+                    if pattern & (1 << wire) != 0 { *candidate &= any; }
+                    else                          { *candidate &= !every; }
+                }
+            }
+
+            // Every segment is driven by exactly one wire
+            for wire in 0..num_wires
+            {
+                match candidates[wire].count_ones()
+                {
+                    0 => return false,
+                    1 =>
+                    {
+                        let segment = candidates[wire];
+                        for (other, candidate) in candidates.iter_mut().enumerate()
+                        {
+                            if other != wire { *candidate &= !segment; }
+                        }
+                    },
+                    _ => continue
+                }
+            }
+            for segment in 0..num_wires
+            {
+                let mut drivers = (0..num_wires).filter(|&wire| candidates[wire] & (1 << segment) != 0);
+                match (drivers.next(), drivers.next())
+                {
+                    (None, _)          => return false,
+                    (Some(wire), None) => candidates[wire] = 1 << segment,
+                    _                  => continue
+                }
+            }
+
+            if candidates == before.as_slice() { return true; }
+        }
+    }
+
+    fn is_glyph_possible(&self, pattern: SegmentMask, glyph: SegmentMask, candidates: &[SegmentMask]) -> bool
+    {
+        if pattern.count_ones() != glyph.count_ones() { return false; }
+
+        // Lit wires must be able to drive a lit segment, and unlit ones an unlit segment
+        return (0..candidates.len()).all(|wire| if pattern & (1 << wire) != 0 { candidates[wire] & glyph  != 0 }
+                                                else                          { candidates[wire] & !glyph != 0 });
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::test_rng::Rng;
+
+    #[test]
+    fn test_seven_segment_example()
+    {
+        let display = DisplayDefinition::seven_segment();
+        let observations = ["acedgfb", "cdfbe", "gcdfa", "fbcad", "dab", "cefabd", "cdfgeb", "eafb", "cagedb", "ab"];
+
+        let masks: Vec<SegmentMask> = observations.iter().map(|p| display.parse_pattern(p).unwrap()).collect();
+        let wirings = display.find_wirings(&masks);
+
+        // d->a, e->b, a->c, f->d, g->e, b->f, c->g
+        assert_eq!(wirings, vec![vec![2, 5, 6, 0, 1, 3, 4]]);
+        assert_eq!(display.decode_entry(&observations, &["cdfeb", "fcadb", "cdfeb", "cdbaf"]), Ok(vec![5, 3, 5, 3]));
+    }
+
+    #[test]
+    fn test_unique_sizes()
+    {
+        assert_eq!(DisplayDefinition::seven_segment().get_unique_sizes(), vec![2, 3, 4, 7]);
+    }
+
+    #[test]
+    fn test_ambiguous_and_contradictory()
+    {
+        let display = DisplayDefinition::seven_segment();
+
+        // A lone 1 can't tell its two wires apart, but it reads as 1 either way
+        assert_eq!(display.decode_entry(&["ab"], &["ab"]), Ok(vec![1]));
+        assert_eq!(display.find_wirings(&[display.parse_pattern("ab").unwrap()]).len(), 2 * 120);
+
+        // 2, 3 and 5 all have five segments
+        assert!(matches!(display.decode_entry(&[], &["abcde"]), Err(DecodeError::Ambiguous(_))));
+
+        // No glyph has six segments and one wire at the same time...
+        assert_eq!(display.decode_entry(&["abcdef", "a"], &[]), Err(DecodeError::Contradictory));
+        // ...or two different patterns of size 2
+        assert_eq!(display.decode_entry(&["ab", "ac"], &[]), Err(DecodeError::Contradictory));
+
+        assert_eq!(display.decode_entry(&["abz"], &[]), Err(DecodeError::UnknownWire('z')));
+    }
+
+    fn scramble(display: &DisplayDefinition, wiring: &[usize], glyph: SegmentMask) -> String
+    {
+        // The wire driving each lit segment
+        return (0..wiring.len()).filter(|&wire| glyph & (1 << wiring[wire]) != 0)
+                                .map(|wire| display.segments[wire])
+                                .collect();
+    }
+
+    fn check_random_wirings(display: &DisplayDefinition, seed: u64)
+    {
+        let mut rng = Rng::new(seed);
+        for _ in 0..20
+        {
+            // Fisher-Yates
+            let mut wiring: Wiring = (0..display.get_num_segments()).collect();
+            for i in (1..wiring.len()).rev()
+            {
+                let j = rng.next(i as u64 + 1) as usize;
+                wiring.swap(i, j);
+            }
+
+            let observations: Vec<String> = display.glyphs.iter().map(|&glyph| scramble(display, &wiring, glyph)).collect();
+            let observations: Vec<&str>   = observations.iter().map(|s| s.as_str()).collect();
+
+            let masks: Vec<SegmentMask> = observations.iter().map(|p| display.parse_pattern(p).unwrap()).collect();
+            assert_eq!(display.find_wirings(&masks), vec![wiring.clone()]);
+
+            let values: Vec<usize> = (0..display.get_num_glyphs()).rev().collect();
+            let outputs: Vec<&str> = values.iter().map(|&value| observations[value]).collect();
+            assert_eq!(display.decode_entry(&observations, &outputs), Ok(values));
+        }
+    }
+
+    #[test]
+    fn test_seven_segment_random_wirings()
+    {
+        check_random_wirings(&DisplayDefinition::seven_segment(), 0x0008_0007);
+    }
+
+    //  aaaaa
+    // fh j kb
+    // f hjk b
+    //  gg mm
+    // e nlp c
+    // en l pc
+    //  ddddd
+    const FOURTEEN_SEGMENT_GLYPHS: [&str; 16] = ["abcdefkn", "bck", "abdegm", "abcdgm", "bcfgm",
+                                                 "adfgp", "acdefgm", "akl", "abcdefgm", "abcdfgm",
+                                                 "abcefgm", "abcdjlm", "adef", "abcdjl", "adefg",
+                                                 "aefg"];
+
+    fn fourteen_segment() -> DisplayDefinition
+    {
+        return DisplayDefinition::new("abcdefghjklmnp", &FOURTEEN_SEGMENT_GLYPHS);
+    }
+
+    #[test]
+    fn test_fourteen_segment_random_wirings()
+    {
+        check_random_wirings(&fourteen_segment(), 0x0014_0014);
+    }
+
+    #[test]
+    fn test_fourteen_segment_ambiguity()
+    {
+        let display = fourteen_segment();
+
+        // Millions of wirings fit, so this only finishes if the search stops early
+        let result = display.decode_entry(&["abcd"], &["abcd"]);
+        let readings = match result
+        {
+            Err(DecodeError::Ambiguous(readings)) => readings,
+            _                                     => panic!("Expected an ambiguous entry, got {:?}", result)
+        };
+        assert_eq!(readings.len(), 2);
+        assert_ne!(readings[0], readings[1]);
+
+        // With every segment lit there's only one glyph it can be, even if
+        // any of the 14! wirings fits
+        let mut glyphs = FOURTEEN_SEGMENT_GLYPHS.to_vec();
+        glyphs.push("abcdefghjklmnp");
+
+        let display = DisplayDefinition::new("abcdefghjklmnp", &glyphs);
+        assert_eq!(display.decode_entry(&["pnmlkjhgfedcba"], &["abcdefghjklmnp"]), Ok(vec![16]));
+    }
+
+    #[test]
+    fn test_sixteen_segment_random_wirings()
+    {
+        // Random glyphs, far from any real font
+        let mut rng = Rng::new(0x0016_0016);
+        let names   = "abcdefghijklmnop";
+
+        let mut glyphs: Vec<String> = Vec::new();
+        while glyphs.len() < 24
+        {
+            let mask  = rng.next(1 << 16);
+            let glyph = names.chars().enumerate().filter(|&(i, _)| mask & (1 << i) != 0).map(|(_, c)| c).collect();
+            if !glyphs.contains(&glyph) { glyphs.push(glyph); }
+        }
+        let glyphs: Vec<&str> = glyphs.iter().map(|s| s.as_str()).collect();
+
+        check_random_wirings(&DisplayDefinition::new(names, &glyphs), 0x1616_1616);
+    }
+}
@@ -0,0 +1,24 @@
+// xorshift64, enough to build varied test cases without any crate
+pub struct Rng
+{
+    state: u64
+}
+
+impl Rng
+{
+    // The seed must not be 0, or every number is 0
+    pub fn new(seed: u64) -> Self
+    {
+        assert!(seed != 0);
+        return Self{ state: seed };
+    }
+
+    // Somewhere in 0..max
+    pub fn next(&mut self, max: u64) -> u64
+    {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state % max;
+    }
+}